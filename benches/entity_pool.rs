@@ -0,0 +1,83 @@
+//! Compares spawning a burst of projectile-like entities from scratch against reusing pooled ones.
+//!
+//! Run with `cargo bench --bench entity_pool`.
+
+use bevy::prelude::*;
+use criterion::{Criterion, criterion_group, criterion_main};
+use short_flight::pool::{self, Pooled};
+
+/// How many entities are spawned per iteration, about what a spread attack fires in a second.
+const BURST: usize = 64;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Leaf;
+
+#[derive(Event)]
+struct Hit;
+
+/// Stands in for the bundle `SpawnProjectile` builds, without needing any assets.
+fn projectile_bundle(i: usize) -> impl Bundle {
+    (
+        Name::new("Leaves"),
+        Transform::from_xyz(i as f32, 0.0, 0.0),
+        Mesh3d::default(),
+        MeshMaterial3d::<StandardMaterial>::default(),
+        Sprite::default(),
+    )
+}
+
+fn spawn_and_despawn(c: &mut Criterion) {
+    let mut world = World::new();
+    let mut burst = Vec::with_capacity(BURST);
+    c.bench_function("spawn_and_despawn", |b| {
+        b.iter(|| {
+            for i in 0..BURST {
+                burst.push(
+                    world
+                        .spawn(projectile_bundle(i))
+                        .observe(|_: Trigger<Hit>| ())
+                        .id(),
+                );
+            }
+            for entity in burst.drain(..) {
+                world.despawn(entity);
+            }
+            world.flush();
+        })
+    });
+}
+
+fn acquire_and_release(c: &mut Criterion) {
+    let mut world = World::new();
+    pool::prewarm(&mut world, Leaf, BURST, |world| {
+        world
+            .spawn(projectile_bundle(0))
+            .observe(|_: Trigger<Hit>| ())
+            .id()
+    });
+    let mut burst = Vec::with_capacity(BURST);
+    c.bench_function("acquire_and_release", |b| {
+        b.iter(|| {
+            for i in 0..BURST {
+                let entity = match pool::acquire(&mut world, Leaf) {
+                    Some(entity) => {
+                        world.entity_mut(entity).insert(projectile_bundle(i));
+                        entity
+                    }
+                    None => world
+                        .spawn((Pooled(Leaf), projectile_bundle(i)))
+                        .observe(|_: Trigger<Hit>| ())
+                        .id(),
+                };
+                burst.push(entity);
+            }
+            for entity in burst.drain(..) {
+                pool::release::<Leaf>(&mut world, entity);
+            }
+            world.flush();
+        })
+    });
+}
+
+criterion_group!(benches, spawn_and_despawn, acquire_and_release);
+criterion_main!(benches);
@@ -28,7 +28,8 @@ pub enum AnimType {
 
 // add default properties to variants here
 impl AnimType {
-    fn can_interrupt(self, other: Option<Self>) -> bool {
+    /// Returns `true` if `other` is allowed to cut this animation short.
+    pub(crate) fn can_interrupt(self, other: Option<Self>) -> bool {
         match self {
             Idle => true,
             Walking => true,
@@ -82,6 +83,9 @@ pub struct AnimationData {
     #[serde(default)]
     #[serde(alias = "direction")]
     pub direction_label: AnimationDirLabel,
+    /// Frame ranges during which a blocking animation can be cancelled into a new move.
+    #[serde(default)]
+    pub cancel_windows: Vec<FrameRange>,
     #[serde(flatten)]
     #[serde(default)]
     #[serde(alias = "can_move")]
@@ -89,6 +93,18 @@ pub struct AnimationData {
     pub blocking_override: Option<bool>,
 }
 
+/// A frame range of an animation, as `(start, end)`.
+///
+/// `start` is inclusive and `end` is exclusive.
+#[derive(Debug, Reflect, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub struct FrameRange(pub f32, pub f32);
+
+impl FrameRange {
+    pub fn contains(&self, frame: f32) -> bool {
+        (self.0..self.1).contains(&frame)
+    }
+}
+
 impl AnimationData {
     // pub const fn time(mut self, time: f32) -> Self {
     //     self.time = time;
@@ -116,6 +132,12 @@ impl AnimationData {
         }
         return self.variant.blocks();
     }
+    /// returns true if `frame` is inside one of the cancel windows
+    pub fn can_cancel(&self, frame: f32) -> bool {
+        self.cancel_windows
+            .iter()
+            .any(|window| window.contains(frame))
+    }
 }
 
 /// Label for what direction(s) this animation is facing.
@@ -1,7 +1,7 @@
 use crate::animation::{AnimType, AnimationData};
 use crate::moves::interfaces::MoveData;
 use crate::projectile::interfaces::ProjectileData;
-use crate::{ldtk, moves, npc, projectile, shaymin};
+use crate::{dialogue, ldtk, moves, npc, projectile, shaymin, typing};
 use bevy::asset::AssetLoader;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
@@ -34,6 +34,10 @@ impl Plugin for AssetsPlugin {
             .register_asset_loader(RonAssetLoader::<ProjectileData>::with_extension(&[
                 "proj.ron",
             ]))
+            .init_asset::<typing::TypeChart>()
+            .register_asset_loader(RonAssetLoader::<typing::TypeChart>::with_extension(&[
+                "chart.ron",
+            ]))
             .add_loading_state(
                 LoadingState::new(ShortFlightLoadingState::First)
                     .load_collection::<ldtk::MapAssets>()
@@ -51,6 +55,8 @@ impl Plugin for AssetsPlugin {
                     .load_collection::<npc::file::NPCAlmanac>()
                     .load_collection::<moves::interfaces::MoveList>()
                     .load_collection::<projectile::interfaces::ProjectileCatalog>()
+                    .load_collection::<typing::TypeChartAssets>()
+                    .load_collection::<dialogue::DialogueLibrary>()
                     .on_failure_continue_to_state(ShortFlightLoadingState::FailState)
                     .continue_to_state(ShortFlightLoadingState::SpawnWithAssets),
             )
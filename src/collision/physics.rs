@@ -1,8 +1,16 @@
 use super::{CollisionExitEvent, TilemapCollision};
+use crate::animation::AnimType;
 use crate::collision::{BasicCollider, CollisionEnterEvent, CollisionLayers, DynamicCollision};
 use crate::ldtk::TileQuery;
-use crate::npc::stats::{Damage, Health};
+use crate::npc::animation::AnimationHandler;
+use crate::npc::stats::{AttackFaction, Damage, Health, Knockback, can_hurt};
+use crate::npc::{Faction, NPCInfo};
+use crate::projectile::chain::ProjectileHit;
+use crate::projectile::interfaces::ProjectileOwner;
+use crate::projectile::rules::ProjectileHistory;
+use crate::shaymin::Shaymin;
 use crate::tile::{TileDepth, TileFlags, TileSlope};
+use crate::typing::{AttackElement, TypeChart, TypeChartAssets, Typing};
 use bevy::color::palettes;
 use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
@@ -184,14 +192,39 @@ pub fn unground_on_leave(
     rigidbody.wall.remove(&trigger.other);
 }
 
+/// Seconds that both the attacker and the target freeze for when a hit lands.
+///
+/// For projectiles, the attacker is whoever fired them.
+const HIT_STOP: f32 = 0.1;
+
 /// If observed by an entity, this entity will collide with projectiles and attacks
+///
+/// Damage is scaled by the [`TypeChart`] if the attack has an [`AttackElement`].
+/// Hits also push the entity away by the attack's [`Knockback`],
+/// briefly freeze both sides with a hit-stop, and play [`AnimType::Hurt`] if it can interrupt.
+/// A projectile never hits the same entity twice, as remembered by its [`ProjectileHistory`].
+/// Attacks don't hurt whoever made them, or their side unless they have friendly fire.
 pub fn take_hits(
     trigger: Trigger<CollisionEnterEvent>,
-    mut this_rigidbody: Query<&mut Health>,
+    mut this_rigidbody: Query<(&mut Health, Option<&Typing>, Option<&NPCInfo>, Has<Shaymin>)>,
     other_col: Query<&BasicCollider>,
-    other_query: Query<&Damage>,
+    other_query: Query<(
+        &Damage,
+        Option<&AttackElement>,
+        Option<&Knockback>,
+        &GlobalTransform,
+        Option<&ChildOf>,
+        Option<&AttackFaction>,
+        Option<&ProjectileOwner>,
+    )>,
+    mut rigidbodies: Query<(&mut Rigidbody, &GlobalTransform)>,
+    mut anims: Query<&mut AnimationHandler>,
+    mut histories: Query<&mut ProjectileHistory>,
+    type_chart: Option<Res<TypeChartAssets>>,
+    type_charts: Res<Assets<TypeChart>>,
+    mut commands: Commands,
 ) {
-    let Ok((mut health)) = this_rigidbody.get_mut(trigger.this) else {
+    let Ok((mut health, typing, info, is_player)) = this_rigidbody.get_mut(trigger.this) else {
         return;
     };
 
@@ -206,9 +239,53 @@ pub fn take_hits(
         return;
     }
 
-    let Ok((damage)) = other_query.get(trigger.other) else {
+    let Ok((damage, attack_element, knockback, attack_transform, child_of, faction, owner)) =
+        other_query.get(trigger.other)
+    else {
         return;
     };
 
-    health.hp -= **damage;
+    // moves are children of their user, and projectiles remember who fired them
+    let attacker = child_of.map_or(trigger.other, ChildOf::parent);
+    let owner = owner.map_or(attacker, |owner| **owner);
+    if !can_hurt(faction, owner, trigger.this, Faction::of(info, is_player)) {
+        return;
+    }
+
+    if let Ok(mut history) = histories.get_mut(trigger.other) {
+        if !history.hit(trigger.this) {
+            return;
+        }
+        commands.queue(ProjectileHit {
+            projectile: trigger.other,
+            target: trigger.this,
+        });
+    }
+
+    let multiplier = match (
+        attack_element,
+        type_chart.and_then(|chart| type_charts.get(&chart.chart)),
+    ) {
+        (Some(attack_element), Some(chart)) => chart.damage_multiplier(attack_element, typing),
+        _ => 1.0,
+    };
+
+    health.hp -= (**damage as f32 * multiplier).round() as i64;
+
+    if let Some(knockback) = knockback
+        && let Ok((mut rigidbody, transform)) = rigidbodies.get_mut(trigger.this)
+    {
+        let direction = (transform.translation().xz() - attack_transform.translation().xz())
+            .normalize_or(Vec2::X);
+        rigidbody.velocity += direction.extend(0.0).xzy() * **knockback;
+    }
+
+    if let Ok(mut anim) = anims.get_mut(trigger.this) {
+        anim.hit_stop(HIT_STOP);
+        anim.interrupt(AnimType::Hurt);
+    }
+    // moves are animated by their user and projectiles have none, so freeze whoever made them instead
+    if let Ok(mut anim) = anims.get_mut(owner) {
+        anim.hit_stop(HIT_STOP);
+    }
 }
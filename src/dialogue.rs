@@ -0,0 +1,465 @@
+use crate::assets::{RonAssetLoader, loaded};
+use crate::moves::Move;
+use crate::npc::companion::{JoinParty, LeaveParty};
+use crate::npc::stats::FacingDirection;
+use crate::shaymin::Shaymin;
+use crate::shaymin::loadout::{ForgetMove, LearnMove};
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use bevy_asset_loader::asset_collection::AssetCollection;
+use serde::{Deserialize, Serialize};
+
+crate::registry::registry_id! {
+    /// The conversation an NPC has when the player talks to them.
+    ///
+    /// The ID of a dialogue is the name of its data file in `dialogue_data`.
+    /// Set in [`NPCData::dialogue`], or by the `Dialogue` field of an NPC placed in LDtk.
+    ///
+    /// [`NPCData::dialogue`]: crate::npc::file::NPCData::dialogue
+    Dialogue
+}
+
+pub struct DialoguePlugin;
+
+impl Plugin for DialoguePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<DialogueData>()
+            .register_asset_loader(RonAssetLoader::<DialogueData>::with_extension(&[
+                "dialogue.ron",
+            ]))
+            .init_resource::<GameFlags>()
+            .register_type::<GameFlags>()
+            .add_systems(
+                Update,
+                (
+                    start_dialogue.run_if(not_in_dialogue),
+                    advance_dialogue.run_if(resource_exists::<ActiveDialogue>),
+                )
+                    .chain()
+                    .run_if(loaded),
+            );
+    }
+}
+
+#[derive(Resource, AssetCollection)]
+pub(crate) struct DialogueLibrary {
+    #[asset(path = "dialogue_data", collection(typed, mapped))]
+    pub data: HashMap<Dialogue, Handle<DialogueData>>,
+}
+
+/// A dialogue tree, made of lines that lead on to each other.
+#[derive(Debug, Asset, TypePath, Serialize, Deserialize, Clone)]
+pub(crate) struct DialogueData {
+    /// The line the conversation starts on.
+    pub start: String,
+    pub lines: HashMap<String, DialogueLine>,
+}
+
+/// Something said in a dialogue.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct DialogueLine {
+    /// Who is speaking, or the NPC being talked to if not set.
+    #[serde(default)]
+    pub speaker: Option<String>,
+    pub text: String,
+    /// Flags set once the line is shown.
+    #[serde(default)]
+    pub set_flags: Vec<String>,
+    #[serde(default)]
+    pub next: DialogueNext,
+}
+
+/// Where a dialogue goes after a line.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub(crate) enum DialogueNext {
+    /// The conversation is over.
+    #[default]
+    End,
+    Goto(String),
+    /// Lets the player pick what to say, out of the choices whose condition holds.
+    Choices(Vec<DialogueChoice>),
+    /// Goes to the line paired with the first condition that holds, or ends if none do.
+    Branch(Vec<(FlagCondition, String)>),
+}
+
+/// Something the player can say back.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct DialogueChoice {
+    pub text: String,
+    /// Only offered if this holds.
+    #[serde(default)]
+    pub condition: Option<FlagCondition>,
+    /// Flags set if picked.
+    #[serde(default)]
+    pub set_flags: Vec<String>,
+    /// Done to the player if picked.
+    #[serde(default)]
+    pub actions: Vec<DialogueAction>,
+    /// The line it leads to, or the end of the conversation if not set.
+    #[serde(default)]
+    pub goto: Option<String>,
+}
+
+/// Something picking a [`DialogueChoice`] does, such as a move tutor teaching the player a move.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) enum DialogueAction {
+    /// Teaches the player a move, if they have a free slot.
+    LearnMove(Move),
+    ForgetMove(Move),
+    /// Has the NPC being talked to join the player's party.
+    JoinParty,
+    /// Dismisses the NPC being talked to from the player's party.
+    LeaveParty,
+}
+
+impl DialogueAction {
+    fn apply(&self, player: Entity, npc: Entity, commands: &mut Commands) {
+        match self {
+            DialogueAction::LearnMove(move_id) => commands.queue(LearnMove {
+                target: player,
+                move_id: *move_id,
+                slot: None,
+            }),
+            DialogueAction::ForgetMove(move_id) => commands.queue(ForgetMove {
+                target: player,
+                move_id: *move_id,
+            }),
+            DialogueAction::JoinParty => commands.queue(JoinParty(npc)),
+            DialogueAction::LeaveParty => commands.queue(LeaveParty(npc)),
+        }
+    }
+}
+
+/// A check on the [`GameFlags`].
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) enum FlagCondition {
+    Set(String),
+    Unset(String),
+    /// Holds if all of them do, including if there are none.
+    All(Vec<FlagCondition>),
+    /// Holds if any of them do.
+    Any(Vec<FlagCondition>),
+}
+
+impl FlagCondition {
+    pub fn holds(&self, flags: &GameFlags) -> bool {
+        match self {
+            FlagCondition::Set(flag) => flags.is_set(flag),
+            FlagCondition::Unset(flag) => !flags.is_set(flag),
+            FlagCondition::All(conditions) => conditions.iter().all(|c| c.holds(flags)),
+            FlagCondition::Any(conditions) => conditions.iter().any(|c| c.holds(flags)),
+        }
+    }
+}
+
+/// Named flags that remember what the player has done, set and checked by dialogues.
+#[derive(Debug, Resource, Reflect, Default)]
+pub struct GameFlags(HashSet<String>);
+
+impl GameFlags {
+    pub fn set(&mut self, flag: impl Into<String>) {
+        self.0.insert(flag.into());
+    }
+
+    pub fn is_set(&self, flag: &str) -> bool {
+        self.0.contains(flag)
+    }
+}
+
+/// The conversation the player is in, if there is one.
+///
+/// Player control and NPC AI are paused for as long as this exists.
+#[derive(Debug, Resource)]
+pub struct ActiveDialogue {
+    pub dialogue: Dialogue,
+    /// The NPC being talked to.
+    pub npc: Entity,
+    line: String,
+    /// How many characters of the line are shown so far.
+    revealed: f32,
+    selected: usize,
+}
+
+/// Run condition that's `true` unless the player is in a conversation.
+pub fn not_in_dialogue(active: Option<Res<ActiveDialogue>>) -> bool {
+    active.is_none()
+}
+
+/// The key that talks to NPCs, and moves dialogue along.
+const INTERACT: KeyCode = KeyCode::KeyJ;
+
+/// How far away the player can talk to NPCs from.
+const INTERACT_RANGE: f32 = 1.5;
+
+/// How far off from facing an NPC the player can be while talking to them, in degrees.
+const INTERACT_ANGLE: f32 = 45.0;
+
+/// How many characters of a line are revealed per second.
+const CHARACTERS_PER_SECOND: f32 = 40.0;
+
+/// Marks the text box of the open dialogue.
+#[derive(Debug, Component)]
+struct DialogueBox;
+
+#[derive(Debug, Component)]
+struct DialogueSpeakerText;
+
+#[derive(Debug, Component)]
+struct DialogueLineText;
+
+#[derive(Debug, Component)]
+struct DialogueChoicesText;
+
+/// Starts talking to the NPC in front of the player when they press [`INTERACT`].
+fn start_dialogue(
+    kb: Res<ButtonInput<KeyCode>>,
+    player: Single<(&GlobalTransform, &FacingDirection), With<Shaymin>>,
+    mut npcs: Query<
+        (
+            Entity,
+            &Dialogue,
+            &GlobalTransform,
+            Option<&mut FacingDirection>,
+        ),
+        Without<Shaymin>,
+    >,
+    library: Option<Res<DialogueLibrary>>,
+    dialogues: Res<Assets<DialogueData>>,
+    mut flags: ResMut<GameFlags>,
+    mut commands: Commands,
+) {
+    if !kb.just_pressed(INTERACT) {
+        return;
+    }
+    let Some(library) = library else {
+        return;
+    };
+    let (player_transform, player_facing) = player.into_inner();
+    let position = player_transform.translation().xz();
+
+    let Some((npc, dialogue, npc_position, facing)) = npcs
+        .iter_mut()
+        .map(|(npc, dialogue, transform, facing)| {
+            (npc, *dialogue, transform.translation().xz(), facing)
+        })
+        .filter(|(_, _, npc_position, _)| {
+            let offset = npc_position - position;
+            offset.length() <= INTERACT_RANGE
+                && player_facing.angle_to(offset).to_degrees().abs() <= INTERACT_ANGLE
+        })
+        .min_by(|(.., a, _), (.., b, _)| {
+            a.distance_squared(position)
+                .total_cmp(&b.distance_squared(position))
+        })
+    else {
+        return;
+    };
+
+    let Some(data) = library
+        .data
+        .get(&dialogue)
+        .and_then(|handle| dialogues.get(handle))
+    else {
+        log::error!("Could not find dialogue data file for {:?}", dialogue);
+        return;
+    };
+
+    // turn to face whoever is talking to them
+    if let Some(mut facing) = facing
+        && let Ok(direction) = Dir2::new(position - npc_position)
+    {
+        facing.set(direction);
+    }
+
+    enter_line(&mut flags, data, &data.start);
+    commands.insert_resource(ActiveDialogue {
+        dialogue,
+        npc,
+        line: data.start.clone(),
+        revealed: 0.0,
+        selected: 0,
+    });
+    spawn_dialogue_box(&mut commands);
+}
+
+/// Sets the flags of a line as it's shown.
+fn enter_line(flags: &mut GameFlags, data: &DialogueData, line: &str) {
+    if let Some(line) = data.lines.get(line) {
+        for flag in &line.set_flags {
+            flags.set(flag.clone());
+        }
+    }
+}
+
+fn spawn_dialogue_box(commands: &mut Commands) {
+    commands
+        .spawn((
+            Name::new("Dialogue Box"),
+            DialogueBox,
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Percent(10.0),
+                right: Val::Percent(10.0),
+                bottom: Val::Px(24.0),
+                min_height: Val::Px(120.0),
+                padding: UiRect::all(Val::Px(16.0)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.1, 0.9)),
+            BorderRadius::all(Val::Px(8.0)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                DialogueSpeakerText,
+                Text::default(),
+                TextFont::from_font_size(18.0),
+                TextColor(Color::srgb(1.0, 0.85, 0.4)),
+            ));
+            parent.spawn((
+                DialogueLineText,
+                Text::default(),
+                TextFont::from_font_size(22.0),
+            ));
+            parent.spawn((
+                DialogueChoicesText,
+                Text::default(),
+                TextFont::from_font_size(20.0),
+            ));
+        });
+}
+
+/// Reveals the current line a few characters at a time, and moves the conversation along on [`INTERACT`].
+///
+/// Pressing it while the line is still being revealed shows all of it at once.
+/// The player picks between choices with up and down.
+fn advance_dialogue(
+    mut active: ResMut<ActiveDialogue>,
+    kb: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    library: Res<DialogueLibrary>,
+    dialogues: Res<Assets<DialogueData>>,
+    mut flags: ResMut<GameFlags>,
+    player: Single<Entity, With<Shaymin>>,
+    names: Query<&Name>,
+    dialogue_box: Query<Entity, With<DialogueBox>>,
+    mut speaker_text: Query<&mut Text, With<DialogueSpeakerText>>,
+    mut line_text: Query<&mut Text, (With<DialogueLineText>, Without<DialogueSpeakerText>)>,
+    mut choices_text: Query<
+        &mut Text,
+        (
+            With<DialogueChoicesText>,
+            Without<DialogueLineText>,
+            Without<DialogueSpeakerText>,
+        ),
+    >,
+    mut commands: Commands,
+) {
+    let end = |commands: &mut Commands| {
+        commands.remove_resource::<ActiveDialogue>();
+        for entity in &dialogue_box {
+            commands.entity(entity).despawn();
+        }
+    };
+
+    let Some((data, line)) = library
+        .data
+        .get(&active.dialogue)
+        .and_then(|handle| dialogues.get(handle))
+        .and_then(|data| Some((data, data.lines.get(&active.line)?)))
+    else {
+        log::error!(
+            "Could not find line {} of dialogue {:?}",
+            active.line,
+            active.dialogue
+        );
+        end(&mut commands);
+        return;
+    };
+
+    let length = line.text.chars().count();
+    active.revealed =
+        (active.revealed + time.delta_secs() * CHARACTERS_PER_SECOND).min(length as f32);
+    let done = active.revealed as usize >= length;
+
+    let choices: Vec<&DialogueChoice> = match &line.next {
+        DialogueNext::Choices(choices) => choices
+            .iter()
+            .filter(|choice| choice.condition.as_ref().is_none_or(|c| c.holds(&flags)))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    // the press that opened the dialogue shouldn't also skip through its first line
+    if !active.is_added() {
+        if done && !choices.is_empty() {
+            if kb.just_pressed(KeyCode::KeyW) || kb.just_pressed(KeyCode::ArrowUp) {
+                active.selected = active.selected.checked_sub(1).unwrap_or(choices.len() - 1);
+            }
+            if kb.just_pressed(KeyCode::KeyS) || kb.just_pressed(KeyCode::ArrowDown) {
+                active.selected = (active.selected + 1) % choices.len();
+            }
+        }
+
+        if kb.just_pressed(INTERACT) {
+            if !done {
+                active.revealed = length as f32;
+            } else {
+                let next = match &line.next {
+                    DialogueNext::End => None,
+                    DialogueNext::Goto(next) => Some(next.clone()),
+                    DialogueNext::Choices(_) => choices.get(active.selected).and_then(|choice| {
+                        for flag in &choice.set_flags {
+                            flags.set(flag.clone());
+                        }
+                        for action in &choice.actions {
+                            action.apply(*player, active.npc, &mut commands);
+                        }
+                        choice.goto.clone()
+                    }),
+                    DialogueNext::Branch(branches) => branches
+                        .iter()
+                        .find(|(condition, _)| condition.holds(&flags))
+                        .map(|(_, next)| next.clone()),
+                };
+                match next {
+                    Some(next) => {
+                        enter_line(&mut flags, data, &next);
+                        active.line = next;
+                        active.revealed = 0.0;
+                        active.selected = 0;
+                    }
+                    None => end(&mut commands),
+                }
+                return;
+            }
+        }
+    }
+
+    let speaker = line
+        .speaker
+        .clone()
+        .or_else(|| names.get(active.npc).ok().map(|name| name.to_string()))
+        .unwrap_or_default();
+    for mut text in &mut speaker_text {
+        text.0 = speaker.clone();
+    }
+    for mut text in &mut line_text {
+        text.0 = line.text.chars().take(active.revealed as usize).collect();
+    }
+    for mut text in &mut choices_text {
+        text.0 = if done {
+            choices
+                .iter()
+                .enumerate()
+                .map(|(i, choice)| {
+                    let cursor = if i == active.selected { "> " } else { "  " };
+                    format!("{}{}\n", cursor, choice.text)
+                })
+                .collect()
+        } else {
+            String::new()
+        };
+    }
+}
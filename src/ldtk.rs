@@ -1,7 +1,9 @@
 use crate::assets::ShortFlightLoadingState;
 use crate::collision::{BasicCollider, ColliderShape, CollisionLayers, StaticCollision, ZHitbox};
+use crate::dialogue::Dialogue;
 use crate::npc;
 use crate::npc::NPC;
+use crate::npc::patrol::{Patrol, PatrolMode};
 use crate::tile::{TileDepth, TileFlags, TileSlope};
 use bevy::color::palettes;
 use bevy::ecs::system::SystemState;
@@ -26,9 +28,11 @@ use std::io::ErrorKind;
 use std::path::PathBuf;
 use thiserror::Error;
 
+mod pathfinding;
 mod query;
 mod tilemap_manager;
 
+pub use pathfinding::{TileSurfaces, tile_cell};
 pub use query::TileQuery;
 pub use tilemap_manager::TilemapManager;
 
@@ -389,6 +393,64 @@ fn spawn_map_components(
                             .to_string()
                     });
 
+                let party = entity
+                    .field_instances
+                    .iter()
+                    .find(|field| field.identifier == "Party")
+                    .and_then(|field| field.value.as_ref())
+                    .and_then(|value| value.as_bool())
+                    .unwrap_or_default();
+
+                let dialogue = entity
+                    .field_instances
+                    .iter()
+                    .find(|field| field.identifier == "Dialogue")
+                    .and_then(|field| field.value.as_ref())
+                    .and_then(|value| value.as_str())
+                    .map(Dialogue::new);
+
+                let squad = entity
+                    .field_instances
+                    .iter()
+                    .find(|field| field.identifier == "Squad")
+                    .and_then(|field| field.value.as_ref())
+                    .and_then(|value| value.as_str())
+                    .map(str::to_string);
+
+                // patrol points are grid cells in the layer, so aim for the middle of each
+                let cell_to_world = |point: &serde_json::Value| {
+                    let cell =
+                        Vec2::new(point["cx"].as_f64()? as f32, point["cy"].as_f64()? as f32);
+                    let px = (cell + Vec2::splat(0.5)) * layer.grid_size as f32;
+                    Some((px / 32.).extend(0.0).xzy())
+                };
+                let patrol_points = entity
+                    .field_instances
+                    .iter()
+                    .find(|field| field.identifier == "Patrol")
+                    .and_then(|field| field.value.as_ref())
+                    .and_then(|value| value.as_array())
+                    .map(|points| points.iter().filter_map(cell_to_world).collect::<Vec<_>>())
+                    .unwrap_or_default();
+                let patrol_mode = entity
+                    .field_instances
+                    .iter()
+                    .find(|field| field.identifier == "PatrolMode")
+                    .and_then(|field| field.value.as_ref())
+                    .and_then(|value| value.as_str())
+                    .map(|mode| match mode {
+                        "PingPong" => PatrolMode::PingPong,
+                        _ => PatrolMode::Loop,
+                    })
+                    .unwrap_or_default();
+                let patrol_wait = entity
+                    .field_instances
+                    .iter()
+                    .find(|field| field.identifier == "PatrolWait")
+                    .and_then(|field| field.value.as_ref())
+                    .and_then(|value| value.as_f64())
+                    .unwrap_or_default() as f32;
+
                 let depth = entity
                     .field_instances
                     .iter()
@@ -405,6 +467,14 @@ fn spawn_map_components(
                     })
                     .unwrap_or_default();
 
+                let patrol = (!patrol_points.is_empty()).then(|| {
+                    let points = patrol_points
+                        .into_iter()
+                        .map(|point| point.with_y(depth as f32) + tilemap_transform.translation)
+                        .collect();
+                    Patrol::new(points, patrol_mode, patrol_wait)
+                });
+
                 commands.queue(npc::commands::SpawnNPC {
                     npc_id: NPC::try_from(id as usize).unwrap(),
                     position: Vec3::new(
@@ -413,7 +483,70 @@ fn spawn_map_components(
                         entity.px[1] as f32 / 32.,
                     ) + tilemap_transform.translation,
                     name,
+                    squad,
+                    patrol,
+                    spawner: None,
+                    dialogue,
+                    party,
                 });
+            } else if entity.tags.contains(&"Spawner".to_string()) {
+                let field = |identifier: &str| {
+                    entity
+                        .field_instances
+                        .iter()
+                        .find(|field| field.identifier == identifier)
+                        .and_then(|field| field.value.as_ref())
+                };
+
+                let id = field("NPC_ID")
+                    .expect("No value found for NPC_ID")
+                    .as_u64()
+                    .expect("Expected unsigned integer for NPC_ID, found something else");
+                let count = field("Count").and_then(|value| value.as_u64()).unwrap_or(1);
+                let interval = field("Interval")
+                    .and_then(|value| value.as_f64())
+                    .unwrap_or_default();
+                let max_alive = field("MaxAlive")
+                    .and_then(|value| value.as_u64())
+                    .unwrap_or(count);
+                let trigger = match field("Trigger").and_then(|value| value.as_str()) {
+                    Some("OnEnterArea") => npc::spawner::SpawnTrigger::OnEnterArea,
+                    Some("AfterWave") => npc::spawner::SpawnTrigger::AfterWave,
+                    _ => npc::spawner::SpawnTrigger::OnStart,
+                };
+                let depth = field("Depth")
+                    .and_then(|value| value.as_f64())
+                    .unwrap_or_default();
+
+                let position = Vec3::new(
+                    entity.px[0] as f32 / 32.,
+                    depth as f32,
+                    entity.px[1] as f32 / 32.,
+                ) + tilemap_transform.translation;
+                // the entity's size in LDtk is the area that triggers it, around its pivot
+                let size = Vec2::new(entity.width as f32, entity.height as f32) / 32.;
+                let pivot = Vec2::new(entity.pivot[0] as f32, entity.pivot[1] as f32);
+                let min = position.xz() - pivot * size;
+                let area = Rect::from_corners(min, min + size);
+
+                let mut spawner = npc::spawner::Spawner::new(
+                    NPC::try_from(id as usize).unwrap(),
+                    count as u32,
+                    interval as f32,
+                    max_alive as u32,
+                    trigger,
+                    area,
+                );
+                if let Some(encounter) = field("Encounter").and_then(|value| value.as_str()) {
+                    let wave = field("Wave").and_then(|value| value.as_u64()).unwrap_or(0);
+                    spawner = spawner.with_encounter(encounter.to_string(), wave as u32);
+                }
+
+                commands.spawn((
+                    Name::new(entity.identifier.clone()),
+                    Transform::from_translation(position),
+                    spawner,
+                ));
             }
         }
 
@@ -0,0 +1,155 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::tile::{TileDepth, TileFlags, TileSlope};
+
+use super::TileQuery;
+
+/// The tile data pathfinding looks at to find how high the ground is.
+pub type TileSurfaces<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static GlobalTransform,
+        &'static TileDepth,
+        &'static TileSlope,
+        &'static TileFlags,
+    ),
+>;
+
+/// How much higher the next tile's edge can be than the current one's and still be walked onto.
+///
+/// Matches how far a [`Rigidbody`] can be pushed up onto the ground.
+///
+/// [`Rigidbody`]: crate::collision::physics::Rigidbody
+const STEP_HEIGHT: f32 = 0.2;
+
+/// How far down a path is allowed to drop off a ledge.
+const MAX_DROP: f32 = 2.0;
+
+/// How much extra it costs to walk across a tile for every unit of height it climbs or descends.
+const SLOPE_COST: f32 = 2.0;
+
+/// How many tiles are searched before giving up on a path.
+const MAX_SEARCHED: usize = 4096;
+
+const NEIGHBOURS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+/// The grid cell of the tile under `world_pos`.
+pub fn tile_cell(world_pos: Vec3) -> IVec2 {
+    world_pos.xz().floor().as_ivec2()
+}
+
+/// The center of a grid cell, at ground level.
+fn cell_center(cell: IVec2) -> Vec2 {
+    cell.as_vec2() + Vec2::splat(0.5)
+}
+
+impl TileQuery<'_, '_> {
+    /// The height of the ground at `point`, if there's a tile in `cell` to stand on.
+    ///
+    /// Points outside the cell are treated as being on its closest edge.
+    pub fn surface_height(&self, tiles: &TileSurfaces, cell: IVec2, point: Vec2) -> Option<f32> {
+        let tile = self.get_tile(cell_center(cell).extend(0.0).xzy())?;
+        let (transform, depth, slope, flags) = tiles.get(tile).ok()?;
+        Some(depth.f32() + slope.get_height_at_point(flags, point - transform.translation().xz()))
+    }
+
+    /// The cost of walking from `cell` to its neighbour in `direction`, or [`None`] if it can't be walked.
+    fn step_cost(&self, tiles: &TileSurfaces, cell: IVec2, direction: IVec2) -> Option<f32> {
+        // don't cut corners past tiles that can't be walked across
+        if direction.x != 0 && direction.y != 0 {
+            self.step_cost(tiles, cell, direction.with_y(0))?;
+            self.step_cost(tiles, cell, direction.with_x(0))?;
+        }
+
+        let next = cell + direction;
+        let edge = cell_center(cell) + direction.as_vec2() * 0.5;
+        let rise =
+            self.surface_height(tiles, next, edge)? - self.surface_height(tiles, cell, edge)?;
+        if !(-MAX_DROP..=STEP_HEIGHT).contains(&rise) {
+            return None;
+        }
+
+        let climb = self.surface_height(tiles, next, cell_center(next))?
+            - self.surface_height(tiles, cell, cell_center(cell))?;
+        Some(direction.as_vec2().length() * (1.0 + climb.abs() * SLOPE_COST))
+    }
+
+    /// Finds a walkable path across the tiles with A*, from `from` to `to`.
+    ///
+    /// Returns the centers of every tile to walk through along the way, in order,
+    /// not including the one `from` is in or the one `to` is in.
+    /// Returns [`None`] if there is no path, or if it would take too long to find one.
+    pub fn find_path(&self, tiles: &TileSurfaces, from: Vec3, to: Vec3) -> Option<Vec<Vec3>> {
+        let start = tile_cell(from);
+        let goal = tile_cell(to);
+        if start == goal {
+            return Some(Vec::new());
+        }
+        let heuristic = |cell: IVec2| cell.as_vec2().distance(goal.as_vec2());
+
+        let mut open = BinaryHeap::new();
+        let mut came_from = HashMap::<IVec2, IVec2>::new();
+        let mut costs = HashMap::<IVec2, f32>::new();
+
+        // costs are never negative, so their bits sort the same way they do
+        open.push(Reverse((heuristic(start).to_bits(), start.x, start.y)));
+        costs.insert(start, 0.0);
+
+        let mut searched = 0;
+        while let Some(Reverse((_, x, y))) = open.pop() {
+            let cell = IVec2::new(x, y);
+            if cell == goal {
+                let mut path = Vec::new();
+                let mut cell = *came_from.get(&goal)?;
+                while cell != start {
+                    let center = cell_center(cell);
+                    let height = self.surface_height(tiles, cell, center)?;
+                    path.push(center.extend(height).xzy());
+                    cell = came_from[&cell];
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            searched += 1;
+            if searched > MAX_SEARCHED {
+                return None;
+            }
+
+            let cost = costs[&cell];
+            for direction in NEIGHBOURS {
+                let Some(step) = self.step_cost(tiles, cell, direction) else {
+                    continue;
+                };
+                let next = cell + direction;
+                let next_cost = cost + step;
+                if costs.get(&next).is_some_and(|known| *known <= next_cost) {
+                    continue;
+                }
+                costs.insert(next, next_cost);
+                came_from.insert(next, cell);
+                open.push(Reverse((
+                    (next_cost + heuristic(next)).to_bits(),
+                    next.x,
+                    next.y,
+                )));
+            }
+        }
+
+        None
+    }
+}
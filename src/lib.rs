@@ -8,6 +8,7 @@ use std::path::{Path, PathBuf};
 use enum_iterator::Sequence;
 use serde::{Deserialize, Serialize};
 
+pub mod pool;
 mod test;
 
 const LOG: bool = false;
@@ -122,6 +123,28 @@ where
         .unwrap_or_else(|| panic!("Could not find a variant for {} [path:{}]", stem, path))
 }
 
+/// Returns the name of an asset, which is its file stem without any extensions,
+/// e.g. `Tackle` for `move_data/Tackle.move.ron`.
+///
+/// Logs an error and returns [`None`] if the path has no usable file name.
+pub fn name_from_asset_path(path: &bevy::asset::AssetPath<'_>) -> Option<String> {
+    let binding = std::path::PathBuf::from(
+        path.path()
+            .file_stem()
+            .or_else(|| {
+                log::error!("Could not get the file stem for {}", path);
+                None
+            })?
+            .to_str()
+            .or_else(|| {
+                log::error!("Could not convert {} to unicode", path);
+                None
+            })?,
+    );
+    let name = binding.file_stem()?.to_str()?;
+    Some(name.to_string())
+}
+
 pub fn try_from_asset_path<T>(path: &bevy::asset::AssetPath<'_>) -> Option<T>
 where
     T: Sequence + bevy::reflect::Enum,
@@ -15,13 +15,16 @@ pub(crate) mod sprite3d;
 pub(crate) mod billboard;
 
 mod assets;
+mod dialogue;
 mod ldtk;
 mod mesh;
 mod moves;
 mod npc;
 mod projectile;
+mod registry;
 mod shaymin;
 mod tile;
+mod typing;
 
 fn main() {
     App::new()
@@ -42,6 +45,7 @@ fn main() {
         // game
         .add_plugins(assets::AssetsPlugin)
         .add_plugins(npc::NPCPlugin)
+        .add_plugins(dialogue::DialoguePlugin)
         .add_plugins(moves::interfaces::MovePlugin)
         .add_plugins(projectile::interfaces::ProjectilePlugin)
         .add_plugins(shaymin::ShayminPlugin)
@@ -54,8 +54,8 @@ struct MeshInfo {
 struct GoSaveTheMesh;
 
 #[derive(Debug, Clone, Copy, Event, Reflect)]
-struct TileChanged {
-    tile: Entity,
+pub(crate) struct TileChanged {
+    pub tile: Entity,
 }
 
 #[derive(Debug, Resource, Reflect, Default)]
@@ -0,0 +1,209 @@
+use super::Move;
+use super::interfaces::{
+    MoveCooldowns, MoveData, MoveInterfaces, MoveList, PowerPoints, SpawnMove, move_ready,
+};
+use super::prelude::*;
+use crate::animation::AnimType;
+use crate::npc::animation::AnimationHandler;
+use crate::npc::stats::Damage;
+use serde::{Deserialize, Serialize};
+
+/// Declares that a move can be charged by holding its bind, as set in [`MoveData::charge`].
+#[derive(Debug, Reflect, Serialize, Deserialize, Clone, Default)]
+pub(crate) struct ChargeData {
+    /// Seconds the bind has to be held to reach each charge level, in ascending order.
+    pub(crate) thresholds: Vec<f32>,
+    /// Extra damage per charge level, as a fraction of the base damage.
+    #[serde(default)]
+    pub(crate) damage_scaling: f32,
+    /// The animation the user loops while charging.
+    #[serde(default)]
+    pub(crate) animation: Option<AnimType>,
+}
+
+impl ChargeData {
+    /// Returns the charge level reached after holding for `held` seconds.
+    pub fn level(&self, held: f32) -> usize {
+        self.thresholds
+            .iter()
+            .take_while(|threshold| held >= **threshold)
+            .count()
+    }
+
+    /// The highest level [`ChargeData::level`] can reach.
+    pub fn max_level(&self) -> usize {
+        self.thresholds.len()
+    }
+
+    pub fn damage_multiplier(&self, level: usize) -> f32 {
+        1.0 + self.damage_scaling * level as f32
+    }
+}
+
+impl MoveData {
+    /// Scales `damage` by the charge level the move was released at.
+    pub fn charged_damage(&self, damage: Damage, level: usize) -> Damage {
+        match &self.charge {
+            Some(charge) if level > 0 => {
+                Damage((*damage as f32 * charge.damage_multiplier(level)).round() as i64)
+            }
+            _ => damage,
+        }
+    }
+}
+
+/// Inserted onto the user while it holds the bind of a chargeable move.
+#[derive(Debug, Component, Reflect, Clone)]
+pub struct ChargingMove {
+    pub move_id: Move,
+    /// Seconds the bind has been held for.
+    pub held: f32,
+    pub level: usize,
+}
+
+/// The charge level a move entity was released at.
+#[derive(Debug, Component, Reflect, Clone, Copy, Default, Deref)]
+pub struct MoveCharge(pub usize);
+
+fn get_move_data(world: &World, move_id: Move) -> Option<MoveData> {
+    let handle = world.resource::<MoveList>().data.get(&move_id)?;
+    world.resource::<Assets<MoveData>>().get(handle).cloned()
+}
+
+/// Starts charging a move for `parent`, if the move is chargeable and ready.
+pub struct StartCharge {
+    pub move_id: Move,
+    pub parent: Entity,
+}
+
+impl Command for StartCharge {
+    fn apply(self, world: &mut World) {
+        let Some(move_data) = get_move_data(world, self.move_id) else {
+            log::error!("Could not find move data file for {:?}", self.move_id);
+            return;
+        };
+        let Some(charge) = &move_data.charge else {
+            log::error!("{:?} cannot be charged", self.move_id);
+            return;
+        };
+
+        let mut user = world.entity_mut(self.parent);
+        if !move_ready(
+            self.move_id,
+            &move_data,
+            user.get::<MoveCooldowns>(),
+            user.get::<PowerPoints>(),
+        ) {
+            return;
+        }
+
+        if let Some(animation) = charge.animation
+            && let Some(mut anim) = user.get_mut::<AnimationHandler>()
+        {
+            anim.start_animation(animation);
+            anim.looping = true;
+        }
+        user.insert(ChargingMove {
+            move_id: self.move_id,
+            held: 0.0,
+            level: 0,
+        });
+
+        world.resource_scope(|world, mut move_interfaces: Mut<MoveInterfaces>| {
+            let interface = move_interfaces.interface_mut(self.move_id);
+            interface.on_charge_start(world, self.parent, &move_data);
+        });
+    }
+}
+
+/// Releases the move `parent` is charging, spawning it at the reached charge level.
+pub struct ReleaseCharge {
+    pub parent: Entity,
+}
+
+impl Command for ReleaseCharge {
+    fn apply(self, world: &mut World) {
+        let Some(charging) = world.entity_mut(self.parent).take::<ChargingMove>() else {
+            return;
+        };
+        if let Some(mut anim) = world.get_mut::<AnimationHandler>(self.parent) {
+            anim.looping = false;
+        }
+        let Some(move_data) = get_move_data(world, charging.move_id) else {
+            return;
+        };
+
+        // `ChargingMove` can be inserted by hand, so don't trust its level to be reachable
+        let level = move_data
+            .charge
+            .as_ref()
+            .map_or(0, |charge| charging.level.min(charge.max_level()));
+
+        let Some(move_entity) = (SpawnMove {
+            move_id: charging.move_id,
+            parent: self.parent,
+            charge: level,
+        })
+        .spawn(world) else {
+            return;
+        };
+
+        world.resource_scope(|world, mut move_interfaces: Mut<MoveInterfaces>| {
+            let interface = move_interfaces.interface_mut(charging.move_id);
+            interface.on_charge_release(world, move_entity, level, &move_data);
+        });
+    }
+}
+
+/// Stops charging without using the move, such as when the user gets interrupted.
+pub struct CancelCharge {
+    pub parent: Entity,
+}
+
+impl Command for CancelCharge {
+    fn apply(self, world: &mut World) {
+        let Some(charging) = world.entity_mut(self.parent).take::<ChargingMove>() else {
+            return;
+        };
+        if let Some(mut anim) = world.get_mut::<AnimationHandler>(self.parent) {
+            anim.start_animation(AnimType::Idle);
+        }
+        let Some(move_data) = get_move_data(world, charging.move_id) else {
+            return;
+        };
+
+        world.resource_scope(|world, mut move_interfaces: Mut<MoveInterfaces>| {
+            let interface = move_interfaces.interface_mut(charging.move_id);
+            interface.on_charge_cancel(world, self.parent, &move_data);
+        });
+    }
+}
+
+/// Builds up the charge of every charging entity, and runs [`MoveComponent::on_charge_tick`].
+pub(crate) fn tick_charging_moves(world: &mut World) {
+    let delta = world.resource::<Time>().delta_secs();
+    let charging: Vec<(Entity, Move)> = world
+        .query::<(Entity, &ChargingMove)>()
+        .iter(world)
+        .map(|(entity, charging)| (entity, charging.move_id))
+        .collect();
+
+    for (user, move_id) in charging {
+        let Some(move_data) = get_move_data(world, move_id) else {
+            continue;
+        };
+        let Some(charge) = &move_data.charge else {
+            continue;
+        };
+
+        let mut charging = world.get_mut::<ChargingMove>(user).unwrap();
+        charging.held += delta;
+        charging.level = charge.level(charging.held);
+        let charging = charging.clone();
+
+        world.resource_scope(|world, mut move_interfaces: Mut<MoveInterfaces>| {
+            let interface = move_interfaces.interface_mut(move_id);
+            interface.on_charge_tick(world, user, &charging, &move_data);
+        });
+    }
+}
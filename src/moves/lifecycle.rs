@@ -0,0 +1,144 @@
+use super::Move;
+use super::interfaces::{MoveData, MoveInfo, MoveInterfaces, PowerPoints, RestorePP};
+use super::prelude::*;
+use crate::animation::AnimType;
+use crate::collision::CollisionEnterEvent;
+use crate::npc::stats::{AttackFaction, Health, Knockback, can_hurt};
+use crate::npc::status::InflictsStatus;
+use crate::npc::{Faction, NPCInfo};
+use crate::shaymin::Shaymin;
+use crate::typing::AttackElement;
+use bevy::ecs::entity_disabling::Disabled;
+use short_flight::pool;
+
+/// The animation the user was playing when this move was spawned.
+///
+/// If the user's animation changes to anything else before it finishes, the move is interrupted.
+#[derive(Debug, Component, Reflect, Clone, Copy, Deref)]
+pub struct MoveAnimation(pub AnimType);
+
+/// Runs [`MoveComponent::on_hit`] for a move that collided with something that has [`Health`],
+/// then refunds [`MoveData::refund`] PP to the user.
+pub struct MoveHit {
+    pub move_entity: Entity,
+    pub target: Entity,
+}
+
+impl Command for MoveHit {
+    fn apply(self, world: &mut World) {
+        let Some((move_id, move_data)) = get_move_data(world, self.move_entity) else {
+            return;
+        };
+
+        world.resource_scope(|world, mut move_interfaces: Mut<MoveInterfaces>| {
+            let interface = move_interfaces.interface_mut(move_id);
+            interface.on_hit(world, self.move_entity, self.target, &move_data);
+        });
+
+        if move_data.refund > 0.0
+            && let Some(user) = world.get::<ChildOf>(self.move_entity).map(ChildOf::parent)
+            && world.get::<PowerPoints>(user).is_some()
+        {
+            RestorePP {
+                target: user,
+                amount: Some(move_data.refund),
+            }
+            .apply(world);
+        }
+    }
+}
+
+/// Ends a move, running its hooks and returning the move entity to the pool.
+///
+/// [`MoveComponent::on_interrupt`] runs first if `interrupted` is set,
+/// [`MoveComponent::on_end`] always runs last.
+pub struct EndMove {
+    pub move_entity: Entity,
+    pub interrupted: bool,
+}
+
+impl Command for EndMove {
+    fn apply(self, world: &mut World) {
+        // the move may have already been ended this frame
+        let Some((move_id, move_data)) = get_move_data(world, self.move_entity) else {
+            recycle_move(world, self.move_entity);
+            return;
+        };
+
+        world.resource_scope(|world, mut move_interfaces: Mut<MoveInterfaces>| {
+            let interface = move_interfaces.interface_mut(move_id);
+            if self.interrupted {
+                interface.on_interrupt(world, self.move_entity, &move_data);
+            }
+            interface.on_end(world, self.move_entity, &move_data);
+        });
+
+        recycle_move(world, self.move_entity);
+    }
+}
+
+/// Components [`SpawnMove`] only adds to some moves,
+/// which are taken off again before the entity goes back to the pool.
+///
+/// [`SpawnMove`]: super::interfaces::SpawnMove
+type MoveExtras = (
+    ChildOf,
+    MoveAnimation,
+    AttackElement,
+    InflictsStatus,
+    Knockback,
+    AttackFaction,
+);
+
+/// Returns a move entity to the pool, or despawns it if it isn't pooled.
+fn recycle_move(world: &mut World, move_entity: Entity) {
+    if !pool::release::<Move>(world, move_entity) {
+        if let Ok(entity) = world.get_entity_mut(move_entity) {
+            entity.despawn();
+        }
+        return;
+    }
+    world.entity_mut(move_entity).remove::<MoveExtras>();
+}
+
+fn get_move_data(world: &World, move_entity: Entity) -> Option<(Move, MoveData)> {
+    // pooled moves keep their data while they wait to be reused
+    if world.get::<Disabled>(move_entity).is_some() {
+        return None;
+    }
+    let info = world.get::<MoveInfo>(move_entity)?;
+    let move_data = world
+        .resource::<Assets<MoveData>>()
+        .get(&info.data)?
+        .clone();
+    Some((info.id, move_data))
+}
+
+/// Observed by every move entity, reporting hits to [`MoveComponent::on_hit`].
+///
+/// Only targets the move is allowed to hurt are reported.
+pub(crate) fn report_move_hits(
+    trigger: Trigger<CollisionEnterEvent>,
+    moves: Query<(&ChildOf, Option<&AttackFaction>)>,
+    targets: Query<(Option<&NPCInfo>, Has<Shaymin>), With<Health>>,
+    mut commands: Commands,
+) {
+    let Ok((info, is_player)) = targets.get(trigger.other) else {
+        return;
+    };
+    let Ok((child_of, faction)) = moves.get(trigger.this) else {
+        return;
+    };
+    if !can_hurt(
+        faction,
+        child_of.parent(),
+        trigger.other,
+        Faction::of(info, is_player),
+    ) {
+        return;
+    }
+    commands.queue(MoveHit {
+        move_entity: trigger.this,
+        target: trigger.other,
+    });
+}
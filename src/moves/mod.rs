@@ -1,69 +1,151 @@
 use bevy::prelude::*;
-use enum_iterator::Sequence;
-use prelude::MoveComponent;
-use serde::{Deserialize, Serialize};
 
 mod prelude {
     pub use super::interfaces::MoveComponent;
     pub use bevy::prelude::*;
 }
 
-pub mod magical_leaf;
-pub mod tackle;
+pub mod charge;
+pub mod lifecycle;
+pub mod scripted;
 pub mod void;
 
-/// Marks this entity as a move, aka an attack, that temporarily exists in the world.
-#[derive(
-    Debug,
-    Component,
-    Default,
-    Reflect,
-    Sequence,
-    Serialize,
-    Deserialize,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Hash,
-)]
-pub enum Move {
-    #[default]
-    Void = 0,
-    Tackle,
-    MagicalLeaf,
+crate::registry::registry_id! {
+    /// Marks this entity as a move, aka an attack, that temporarily exists in the world.
+    ///
+    /// The ID of a move is the name of its data file in `move_data`,
+    /// and its [`MoveComponent`] is added with [`RegisterMoveExt::register_move`].
+    ///
+    /// [`MoveComponent`]: interfaces::MoveComponent
+    /// [`RegisterMoveExt::register_move`]: interfaces::RegisterMoveExt::register_move
+    Move
 }
 
-fn register_component(input: Move) -> Box<dyn MoveComponent> {
-    match input {
-        Move::Void => Box::new(void::VoidedMove),
-        Move::Tackle => Box::new(tackle::Tackle),
-        Move::MagicalLeaf => Box::new(magical_leaf::MagicalLeaf),
-        // _ => void,
-    }
+/// Registers the moves that need more than their data file.
+fn register_moves(app: &mut App) {
+    use interfaces::RegisterMoveExt;
+    app.register_move("Void", void::VoidedMove);
 }
 
 pub mod interfaces {
     use super::Move;
+    use super::charge::{ChargeData, ChargingMove, MoveCharge};
+    use super::lifecycle::{EndMove, MoveAnimation};
     use super::prelude::*;
-    use super::register_component;
-    use crate::animation::AnimType;
+    use super::register_moves;
+    use super::scripted::ScriptedMove;
+    use crate::animation::{AnimType, FrameRange};
     use crate::assets::AnimationSpritesheet;
     use crate::collision::ColliderShape;
+    use crate::npc::Faction;
     use crate::npc::animation::AnimationHandler;
-    use crate::npc::stats::Damage;
+    use crate::npc::stats::{AttackFaction, Damage, Knockback};
+    use crate::npc::status::{InflictsStatus, StatusInflict};
+    use crate::projectile::Projectile;
+    use crate::projectile::pattern::EmissionPattern;
+    use crate::typing::{AttackElement, ElementType};
+    use bevy::ecs::entity_disabling::Disabled;
     use bevy::platform::collections::HashMap;
     use bevy_asset_loader::asset_collection::AssetCollection;
-    use bevy_asset_loader::mapped::MapKey;
     use serde::{Deserialize, Serialize};
+    use short_flight::pool::{self, Pooled};
 
     #[derive(
         Debug, Component, Reflect, Clone, Deref, DerefMut, Default, Serialize, Deserialize,
     )]
     #[serde(transparent)]
+    #[require(MoveCooldowns)]
     pub struct Moves(pub Vec<Move>);
 
+    /// Time left, in seconds, before each move can be used again.
+    ///
+    /// Moves without an entry are ready.
+    #[derive(Debug, Component, Reflect, Clone, Deref, DerefMut, Default)]
+    pub struct MoveCooldowns(HashMap<Move, f32>);
+
+    impl MoveCooldowns {
+        pub fn is_ready(&self, move_id: Move) -> bool {
+            self.remaining(move_id) <= 0.0
+        }
+
+        pub fn remaining(&self, move_id: Move) -> f32 {
+            self.get(&move_id).copied().unwrap_or_default()
+        }
+    }
+
+    /// The pool of PP that moves draw from when used.
+    ///
+    /// Entities without this component can use any move for free.
+    #[derive(Debug, Component, Reflect, Clone, Default, Serialize, Deserialize)]
+    pub struct PowerPoints {
+        /// Filled up to `max` when the entity is spawned.
+        #[serde(skip)]
+        pub current: f32,
+        pub max: f32,
+        /// PP restored per second.
+        #[serde(default)]
+        pub regen: f32,
+    }
+
+    impl PowerPoints {
+        pub fn new(max: f32, regen: f32) -> Self {
+            Self {
+                current: max,
+                max,
+                regen,
+            }
+        }
+
+        pub fn can_afford(&self, cost: f32) -> bool {
+            self.current >= cost
+        }
+
+        /// Returns `false` and leaves the pool untouched if there isn't enough PP.
+        pub fn spend(&mut self, cost: f32) -> bool {
+            if !self.can_afford(cost) {
+                return false;
+            }
+            self.current -= cost;
+            true
+        }
+
+        pub fn restore(&mut self, amount: f32) {
+            self.current = (self.current + amount).min(self.max);
+        }
+
+        pub fn restore_all(&mut self) {
+            self.current = self.max;
+        }
+    }
+
+    /// Returns `true` if the move is off cooldown and the user has the PP to pay for it.
+    pub(crate) fn move_ready(
+        move_id: Move,
+        move_data: &MoveData,
+        cooldowns: Option<&MoveCooldowns>,
+        power_points: Option<&PowerPoints>,
+    ) -> bool {
+        cooldowns.is_none_or(|cooldowns| cooldowns.is_ready(move_id))
+            && power_points.is_none_or(|pp| pp.can_afford(move_data.cost))
+    }
+
+    pub(crate) fn tick_move_cooldowns(
+        mut cooldowns: Query<&mut MoveCooldowns>,
+        mut power_points: Query<&mut PowerPoints>,
+        time: Res<Time>,
+    ) {
+        for mut cooldowns in &mut cooldowns {
+            cooldowns.retain(|_, remaining| {
+                *remaining -= time.delta_secs();
+                *remaining > 0.0
+            });
+        }
+        for mut pp in &mut power_points {
+            let regen = pp.regen * time.delta_secs();
+            pp.restore(regen);
+        }
+    }
+
     /// This component operates with the move system
     pub trait MoveComponent: Send + Sync {
         /// Initialize any useful schedules here.
@@ -79,6 +161,55 @@ pub mod interfaces {
             // <Self as MoveComponent>::set_animation(world, entity, AnimType::AttackTackle, None);
         }
 
+        /// Called when `user` starts holding the bind of a chargeable move.
+        ///
+        /// No move entity exists yet while charging.
+        fn on_charge_start(&mut self, world: &mut World, user: Entity, move_data: &MoveData) {}
+
+        /// Called every frame while `user` keeps holding the bind.
+        fn on_charge_tick(
+            &mut self,
+            world: &mut World,
+            user: Entity,
+            charge: &ChargingMove,
+            move_data: &MoveData,
+        ) {
+        }
+
+        /// Called when the bind is released, right after [`MoveComponent::on_spawn`].
+        ///
+        /// `charge_level` is `0` if the bind was released before the first threshold.
+        fn on_charge_release(
+            &mut self,
+            world: &mut World,
+            move_entity: Entity,
+            charge_level: usize,
+            move_data: &MoveData,
+        ) {
+        }
+
+        /// Called when charging stops without the move being used.
+        fn on_charge_cancel(&mut self, world: &mut World, user: Entity, move_data: &MoveData) {}
+
+        /// Called when the move entity collides with something that has [`Health`].
+        ///
+        /// [`Health`]: crate::npc::stats::Health
+        fn on_hit(
+            &mut self,
+            world: &mut World,
+            move_entity: Entity,
+            target: Entity,
+            move_data: &MoveData,
+        ) {
+        }
+
+        /// Called right before the move entity is removed, whether it finished or not.
+        fn on_end(&mut self, world: &mut World, move_entity: Entity, move_data: &MoveData) {}
+
+        /// Called before [`MoveComponent::on_end`] if the user's animation changed
+        /// before the move finished, such as when the user gets hurt or chains into another move.
+        fn on_interrupt(&mut self, world: &mut World, move_entity: Entity, move_data: &MoveData) {}
+
         fn set_animation(world: &mut World, move_entity: Entity, animation: AnimType)
         where
             Self: Sized,
@@ -101,16 +232,21 @@ pub mod interfaces {
 
     impl Plugin for MovePlugin {
         fn build(&self, app: &mut App) {
-            let mut move_interfaces = MoveInterfaces(
-                enum_iterator::all::<Move>()
-                    .into_iter()
-                    .map(|input| (input, register_component(input)))
-                    .collect(),
-            );
-            move_interfaces.iter_mut().for_each(|(_, registration)| {
-                registration.build(app);
-            });
-            app.insert_resource(move_interfaces);
+            app.init_resource::<MoveInterfaces>();
+            register_moves(app);
+            app.register_type::<super::scripted::ScriptedMoveState>()
+                .register_type::<MoveCooldowns>()
+                .register_type::<PowerPoints>()
+                .register_type::<ChargingMove>()
+                .register_type::<MoveAnimation>()
+                .add_systems(
+                    FixedUpdate,
+                    (
+                        tick_move_cooldowns,
+                        super::charge::tick_charging_moves,
+                        super::scripted::run_scripted_moves,
+                    ),
+                );
         }
     }
 
@@ -120,6 +256,7 @@ pub mod interfaces {
         pub(crate) data: Handle<MoveData>,
     }
 
+    /// Every move data file and image, keyed by the [`Move`] named after the file.
     #[derive(Resource, AssetCollection)]
     pub(crate) struct MoveList {
         #[asset(path = "move_data", collection(typed, mapped))]
@@ -129,44 +266,214 @@ pub mod interfaces {
         pub image: HashMap<Move, Handle<Image>>,
     }
 
-    #[derive(Resource, Deref, DerefMut)]
-    pub(crate) struct MoveInterfaces(HashMap<Move, Box<dyn MoveComponent>>);
+    /// The registry of every [`MoveComponent`], keyed by the ID of the move it runs.
+    ///
+    /// Moves that have a data file but were never registered are run by [`ScriptedMove`].
+    #[derive(Resource, Deref, DerefMut, Default)]
+    pub struct MoveInterfaces(HashMap<Move, Box<dyn MoveComponent>>);
+
+    impl MoveInterfaces {
+        /// Returns the interface of the move, falling back to [`ScriptedMove`].
+        pub fn interface_mut(&mut self, move_id: Move) -> &mut Box<dyn MoveComponent> {
+            self.0
+                .entry(move_id)
+                .or_insert_with(|| Box::new(ScriptedMove))
+        }
+    }
+
+    /// Lets any plugin add new moves at build time.
+    pub trait RegisterMoveExt {
+        /// Registers `component` to run the move named `name`, and calls [`MoveComponent::build`].
+        fn register_move(
+            &mut self,
+            name: &str,
+            component: impl MoveComponent + 'static,
+        ) -> &mut Self;
+    }
+
+    impl RegisterMoveExt for App {
+        fn register_move(
+            &mut self,
+            name: &str,
+            mut component: impl MoveComponent + 'static,
+        ) -> &mut Self {
+            component.build(self);
+            let move_id = Move::new(name);
+            let mut move_interfaces = self.world_mut().get_resource_or_init::<MoveInterfaces>();
+            if move_interfaces
+                .insert(move_id, Box::new(component))
+                .is_some()
+            {
+                log::warn!("{} was registered more than once", move_id);
+            }
+            self
+        }
+    }
 
+    /// Describes a move completely.
+    ///
+    /// Moves without a custom [`MoveComponent`] are run by [`super::scripted::ScriptedMove`],
+    /// which interprets the timeline fields below every frame of the user's animation.
     #[derive(Debug, Asset, Reflect, Serialize, Deserialize, Clone, Default)]
     pub(crate) struct MoveData {
         pub(crate) display_name: String,
+        #[serde(default)]
         pub(crate) spritesheet: Option<AnimationSpritesheet>,
+        #[serde(default)]
         pub(crate) collider: Option<ColliderShape>,
-        pub(crate) related_animaition: Option<AnimType>,
-        #[serde(flatten)]
+        /// The animation the user plays while the move is active.
+        #[serde(default)]
+        #[serde(alias = "related_animaition")]
+        pub(crate) related_animation: Option<AnimType>,
+        /// How close the target needs to be before the AI considers using this move.
+        #[serde(default)]
+        pub(crate) range: Option<f32>,
+        /// Base damage of the move. Hitboxes can override this per window.
+        #[serde(default)]
+        pub(crate) damage: Option<Damage>,
+        /// The type of the move, used to scale damage with the [`crate::typing::TypeChart`].
+        #[serde(default)]
+        pub(crate) element: Option<ElementType>,
+        /// A status condition the move can inflict on hit.
+        #[serde(default)]
+        pub(crate) status: Option<StatusInflict>,
+        /// How hard the move pushes whatever it hits away.
+        #[serde(default)]
+        pub(crate) knockback: f32,
+        /// Lets the move hurt the user's own side, though never the user.
+        #[serde(default)]
+        pub(crate) friendly_fire: bool,
+        /// Windows of the animation in which the move can hit something.
+        #[serde(default)]
+        pub(crate) hitboxes: Vec<MoveHitbox>,
+        /// Velocity changes applied to the user during the animation.
+        #[serde(default)]
+        pub(crate) impulses: Vec<MoveImpulse>,
+        /// Projectiles fired once the animation reaches a given frame.
+        #[serde(default)]
+        pub(crate) projectiles: Vec<MoveProjectile>,
+        /// Seconds before the move can be used again.
+        #[serde(default)]
+        pub(crate) cooldown: f32,
+        /// PP drawn from the user's [`PowerPoints`] on use.
+        #[serde(default)]
+        pub(crate) cost: f32,
+        /// PP given back to the user each time the move hits something.
+        #[serde(default)]
+        pub(crate) refund: f32,
+        /// If set, the move is charged by holding its bind instead of firing right away.
+        #[serde(default)]
+        pub(crate) charge: Option<ChargeData>,
+        /// Anything a custom [`MoveComponent`] wants to read that isn't covered above.
+        #[serde(default)]
         #[reflect(ignore)]
         pub(crate) extra_info: HashMap<String, ron::Value>,
     }
 
-    impl MapKey for Move {
-        fn from_asset_path(path: &bevy::asset::AssetPath) -> Self {
-            short_flight::from_asset_path(path)
-        }
+    /// A collider that is only active during part of a move.
+    #[derive(Debug, Reflect, Serialize, Deserialize, Clone)]
+    pub(crate) struct MoveHitbox {
+        pub(crate) frames: FrameRange,
+        pub(crate) collider: ColliderShape,
+        /// Offset from the user, where `x` is forwards along the facing direction
+        /// and `y` is to the right of it.
+        #[serde(default)]
+        pub(crate) offset: Vec2,
+        /// Overrides [`MoveData::damage`] while this hitbox is active.
+        #[serde(default)]
+        pub(crate) damage: Option<Damage>,
+    }
+
+    /// Changes the user's velocity while the animation is inside of `frames`.
+    #[derive(Debug, Reflect, Serialize, Deserialize, Clone, Default)]
+    pub(crate) struct MoveImpulse {
+        pub(crate) frames: FrameRange,
+        /// Added to the horizontal velocity along the facing direction, per second.
+        #[serde(default)]
+        pub(crate) accelerate: f32,
+        /// Sets the horizontal speed, keeping the current direction of travel.
+        #[serde(default)]
+        pub(crate) speed: Option<f32>,
+        /// Sets the vertical velocity.
+        #[serde(default)]
+        pub(crate) lift: Option<f32>,
+    }
+
+    /// Fires a projectile when the animation reaches `frame`.
+    #[derive(Debug, Reflect, Serialize, Deserialize, Clone, Default)]
+    pub(crate) struct MoveProjectile {
+        #[serde(default)]
+        pub(crate) frame: f32,
+        pub(crate) projectile: Projectile,
+        /// How many projectiles are fired, and in which directions.
+        #[serde(default)]
+        pub(crate) pattern: EmissionPattern,
+        /// Projectiles added to the pattern for every charge level.
+        #[serde(default)]
+        pub(crate) extra_per_charge: u32,
+        /// Rotation from the facing direction, in degrees.
+        #[serde(default)]
+        pub(crate) angle: f32,
+        /// Offset from the user, where `x` is forwards along the facing direction
+        /// and `y` is to the right of it.
+        #[serde(default)]
+        pub(crate) offset: Vec2,
     }
 
     pub struct SpawnMove {
         pub move_id: Move,
         pub parent: Entity,
+        /// The charge level the move was released at, `0` for uncharged moves.
+        pub charge: usize,
     }
 
     impl Command for SpawnMove {
         fn apply(self, world: &mut World) {
+            self.spawn(world);
+        }
+    }
+
+    impl SpawnMove {
+        /// Spawns the move, returning the move entity if it was ready to be used.
+        pub fn spawn(self, world: &mut World) -> Option<Entity> {
             let move_list = world.resource::<MoveList>();
-            let Some(handle) = move_list.data.get(&self.move_id) else {
+            let Some(handle) = move_list.data.get(&self.move_id).cloned() else {
                 log::error!("Could not find move data file for {:?}", self.move_id);
-                return;
+                return None;
             };
 
-            let move_data = world
-                .resource::<Assets<MoveData>>()
-                .get(handle)
-                .unwrap()
-                .clone();
+            let Some(move_data) = world.resource::<Assets<MoveData>>().get(&handle).cloned() else {
+                log::error!("Move data for {:?} is not loaded", self.move_id);
+                return None;
+            };
+
+            let user = world.entity(self.parent);
+            if !move_ready(
+                self.move_id,
+                &move_data,
+                user.get::<MoveCooldowns>(),
+                user.get::<PowerPoints>(),
+            ) {
+                log::debug!("{:?} is not ready for {}", self.move_id, self.parent);
+                return None;
+            }
+            // chaining into a new move cuts the previous one short
+            let previous_moves: Vec<Entity> = world
+                .get::<Children>(self.parent)
+                .map(|children| {
+                    children
+                        .iter()
+                        .filter(|child| world.get::<MoveInfo>(*child).is_some())
+                        .collect()
+                })
+                .unwrap_or_default();
+            for move_entity in previous_moves {
+                EndMove {
+                    move_entity,
+                    interrupted: true,
+                }
+                .apply(world);
+            }
 
             let move_bundle = (
                 Name::new(move_data.display_name.clone()),
@@ -175,22 +482,111 @@ pub mod interfaces {
                     id: self.move_id,
                     data: handle.clone(),
                 },
-                Damage(2),
+                move_data
+                    .charged_damage(move_data.damage.clone().unwrap_or(Damage(2)), self.charge),
+                MoveCharge(self.charge),
                 Transform::IDENTITY,
                 ChildOf(self.parent),
             );
 
-            let move_entity = world.spawn(move_bundle).id();
+            let move_entity = match pool::acquire(world, self.move_id) {
+                Some(move_entity) => {
+                    world.entity_mut(move_entity).insert(move_bundle);
+                    move_entity
+                }
+                // observers are kept with the entity while it's pooled, so only new moves need one
+                None => world
+                    .spawn((Pooled(self.move_id), move_bundle))
+                    .observe(super::lifecycle::report_move_hits)
+                    .id(),
+            };
+
+            if let Some(element) = move_data.element {
+                let attack_element = AttackElement::for_user(world, element, self.parent);
+                world.entity_mut(move_entity).insert(attack_element);
+            }
+
+            if let Some(status) = move_data.status.clone() {
+                world.entity_mut(move_entity).insert(InflictsStatus(status));
+            }
+
+            if move_data.knockback > 0.0 {
+                world
+                    .entity_mut(move_entity)
+                    .insert(Knockback(move_data.knockback));
+            }
+
+            if let Some(faction) = Faction::of_entity(world, self.parent) {
+                world.entity_mut(move_entity).insert(AttackFaction {
+                    faction,
+                    friendly_fire: move_data.friendly_fire,
+                });
+            }
 
             // log::info!("spawned move:{}, parent:{}", move_entity, self.parent);
 
             world.resource_scope(|world, mut move_interfaces: Mut<MoveInterfaces>| {
-                let Some(interface) = move_interfaces.0.get_mut(&self.move_id) else {
-                    log::error!("No interface found for {:?}", self.move_id);
-                    return;
-                };
-                interface.on_spawn(world, move_entity, &move_data);
+                move_interfaces.interface_mut(self.move_id).on_spawn(
+                    world,
+                    move_entity,
+                    &move_data,
+                );
             });
+
+            // the move only costs anything once it actually made it into the world
+            if !world
+                .get_entity(move_entity)
+                .is_ok_and(|entity| !entity.contains::<Disabled>())
+            {
+                log::error!("{:?} was gone before it finished spawning", self.move_id);
+                return None;
+            }
+            let mut user = world.entity_mut(self.parent);
+            if let Some(mut pp) = user.get_mut::<PowerPoints>() {
+                pp.spend(move_data.cost);
+            }
+            if move_data.cooldown > 0.0 {
+                match user.get_mut::<MoveCooldowns>() {
+                    Some(mut cooldowns) => {
+                        cooldowns.insert(self.move_id, move_data.cooldown);
+                    }
+                    None => {
+                        user.insert(MoveCooldowns(HashMap::from_iter([(
+                            self.move_id,
+                            move_data.cooldown,
+                        )])));
+                    }
+                }
+            }
+
+            // remember what the user was doing once the move started, to tell when it gets interrupted
+            if let Some(anim) = world.get::<AnimationHandler>(self.parent) {
+                let animation = MoveAnimation(anim.current());
+                world.entity_mut(move_entity).insert(animation);
+            }
+
+            Some(move_entity)
+        }
+    }
+
+    /// Restores PP to the target's [`PowerPoints`].
+    ///
+    /// Restores the pool to full if `amount` is [`None`].
+    pub struct RestorePP {
+        pub target: Entity,
+        pub amount: Option<f32>,
+    }
+
+    impl Command for RestorePP {
+        fn apply(self, world: &mut World) {
+            let Some(mut pp) = world.get_mut::<PowerPoints>(self.target) else {
+                log::error!("{} has no PP to restore", self.target);
+                return;
+            };
+            match self.amount {
+                Some(amount) => pp.restore(amount),
+                None => pp.restore_all(),
+            }
         }
     }
 }
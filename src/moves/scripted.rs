@@ -0,0 +1,171 @@
+use super::charge::MoveCharge;
+use super::interfaces::{MoveData, MoveInfo};
+use super::prelude::*;
+use crate::collision::physics::Rigidbody;
+use crate::collision::{BasicCollider, ColliderShape, CollisionLayers, DynamicCollision, ZHitbox};
+use crate::npc::animation::AnimationHandler;
+use crate::npc::stats::{Damage, FacingDirection};
+use crate::projectile::pattern::SpawnProjectilePattern;
+
+/// Runs any move that has no hand-written [`MoveComponent`],
+/// entirely from the timeline described in its [`MoveData`].
+#[derive(Debug)]
+pub struct ScriptedMove;
+
+impl MoveComponent for ScriptedMove {
+    fn build(&mut self, _app: &mut App) {
+        // the interpreter is shared between every scripted move, see `run_scripted_moves`
+    }
+
+    fn on_spawn(&mut self, world: &mut World, move_entity: Entity, move_data: &MoveData) {
+        world
+            .entity_mut(move_entity)
+            .insert(ScriptedMoveState::default());
+
+        if !move_data.hitboxes.is_empty() {
+            // hitboxes start disabled, and are switched on once the animation reaches them
+            world.entity_mut(move_entity).insert((
+                BasicCollider::new(
+                    true,
+                    ColliderShape::default(),
+                    CollisionLayers::None,
+                    CollisionLayers::None,
+                ),
+                ZHitbox {
+                    y_tolerance: 1.0,
+                    neg_y_tolerance: 0.0,
+                },
+                DynamicCollision,
+            ));
+        }
+
+        if let Some(animation) = move_data.related_animation {
+            Self::set_animation(world, move_entity, animation);
+        }
+    }
+}
+
+/// Per-entity progress of a [`ScriptedMove`] through its timeline.
+#[derive(Debug, Component, Reflect, Default)]
+pub struct ScriptedMoveState {
+    /// Index into [`MoveData::hitboxes`] of the hitbox that is currently active.
+    active_hitbox: Option<usize>,
+    /// Indices into [`MoveData::projectiles`] that have already been fired.
+    fired: Vec<usize>,
+}
+
+/// Converts an offset relative to the facing direction into a world space offset.
+///
+/// `x` is forwards along `facing`, and `y` is to the right of it.
+pub(crate) fn relative_to_facing(facing: Dir2, offset: Vec2) -> Vec3 {
+    let forward = *facing;
+    let right = forward.perp();
+    (forward * offset.x + right * offset.y).extend(0.0).xzy()
+}
+
+pub(crate) fn run_scripted_moves(
+    mut moves: Query<(
+        Entity,
+        &MoveInfo,
+        &mut ScriptedMoveState,
+        &ChildOf,
+        Option<&mut BasicCollider>,
+        &mut Transform,
+        &mut Damage,
+        &MoveCharge,
+    )>,
+    mut users: Query<
+        (
+            &AnimationHandler,
+            &FacingDirection,
+            &GlobalTransform,
+            Option<&mut Rigidbody>,
+        ),
+        Without<ScriptedMoveState>,
+    >,
+    move_data: Res<Assets<MoveData>>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, info, mut state, child_of, collider, mut transform, mut damage, charge) in
+        &mut moves
+    {
+        let Some(data) = move_data.get(&info.data) else {
+            continue;
+        };
+        let Ok((anim, facing, user_transform, rigidbody)) = users.get_mut(child_of.parent()) else {
+            continue;
+        };
+
+        let frame = anim.time();
+
+        if let Some(mut rigidbody) = rigidbody {
+            for impulse in data
+                .impulses
+                .iter()
+                .filter(|impulse| impulse.frames.contains(frame))
+            {
+                let mut horizontal =
+                    rigidbody.velocity.xz() + **facing * impulse.accelerate * time.delta_secs();
+                if let Some(speed) = impulse.speed {
+                    horizontal = horizontal.normalize_or(facing.as_vec2()) * speed;
+                }
+                rigidbody.velocity = horizontal
+                    .extend(impulse.lift.unwrap_or(rigidbody.velocity.y))
+                    .xzy();
+            }
+        }
+
+        let active_hitbox = data
+            .hitboxes
+            .iter()
+            .position(|hitbox| hitbox.frames.contains(frame));
+
+        if let Some(mut collider) = collider {
+            if active_hitbox != state.active_hitbox {
+                // forget previous overlaps so that every window can land its own hit
+                collider.currently_colliding.clear();
+                match active_hitbox.map(|index| &data.hitboxes[index]) {
+                    Some(hitbox) => {
+                        collider.shape = hitbox.collider.clone();
+                        collider.layers = CollisionLayers::Attack;
+                        collider.can_interact = CollisionLayers::NPC;
+                        if let Some(hitbox_damage) =
+                            hitbox.damage.clone().or_else(|| data.damage.clone())
+                        {
+                            *damage = data.charged_damage(hitbox_damage, **charge);
+                        }
+                    }
+                    None => {
+                        collider.layers = CollisionLayers::None;
+                        collider.can_interact = CollisionLayers::None;
+                    }
+                }
+            }
+
+            if let Some(hitbox) = active_hitbox.map(|index| &data.hitboxes[index]) {
+                // the move is a child of the user, so undo the user's rotation to keep the offset in world space
+                transform.translation = user_transform.rotation().inverse()
+                    * relative_to_facing(**facing, hitbox.offset);
+            }
+        }
+        state.active_hitbox = active_hitbox;
+
+        for (index, emission) in data.projectiles.iter().enumerate() {
+            if frame < emission.frame || state.fired.contains(&index) {
+                continue;
+            }
+            state.fired.push(index);
+            commands.queue(SpawnProjectilePattern {
+                source: Some(entity),
+                position: user_transform.translation()
+                    + relative_to_facing(**facing, emission.offset),
+                direction: Rot2::degrees(emission.angle) * **facing,
+                projectile_id: emission.projectile,
+                pattern: emission
+                    .pattern
+                    .with_extra(emission.extra_per_charge * **charge as u32),
+            });
+        }
+    }
+}
@@ -1,14 +1,19 @@
 use crate::animation::AnimType;
 use bevy::prelude::*;
 
+use crate::ldtk::{TileQuery, TileSurfaces};
 use crate::moves::Move;
-use crate::moves::interfaces::{MoveData, MoveInterfaces, MoveList, Moves, SpawnMove};
-use crate::moves::tackle::Tackle;
+use crate::moves::interfaces::{
+    MoveCooldowns, MoveData, MoveInterfaces, MoveList, Moves, PowerPoints, SpawnMove, move_ready,
+};
 use crate::shaymin::Shaymin;
 
 use super::NPCInfo;
 use super::animation::AnimationHandler;
-use super::stats::FacingDirection;
+use super::behaviour::{self, Behaviour, BehaviourContext};
+use super::perception::Perception;
+use super::stats::{FacingDirection, Health};
+use super::status::StatusCondition;
 
 /// Describes the various states an NPC can be in,
 /// which influences how their AI makes decisions
@@ -21,6 +26,8 @@ pub enum NPCActions {
     Defensive,
     /// Will do nada. If `explore` is not zero, then pick a direction and follow it as best as can be.
     Idle { explore: Vec3 },
+    /// Gave up on a chase, and is walking back to `home` before doing anything else.
+    Return { home: Vec3 },
 }
 
 /// What the enemy will choose to do in any given frame
@@ -35,6 +42,12 @@ pub enum NPCDesicion {
     Move {
         target: Vec3,
     },
+    /// Will find a way to walk to `destination`, which is in world space.
+    ///
+    /// Turned into [`NPCDesicion::Move`]s along a path by [`follow_paths`](super::navigation::follow_paths).
+    MoveTo {
+        destination: Vec3,
+    },
     /// Will use a move to try and attack the player.
     /// The ambition is as simple as the move.
     BasicAttack {
@@ -51,112 +64,129 @@ impl Default for NPCActions {
     }
 }
 
+/// The [`Behaviour`] an NPC decides what to do with, taken from its [`NPCData`].
+///
+/// [`NPCData`]: super::file::NPCData
+#[derive(Debug, Component, Clone, Default, Deref)]
+pub struct BehaviourTree(pub Behaviour);
+
 pub(crate) fn run_enemy_npc_ai(
     query: Query<
         (
             Entity,
-            Option<(&NPCInfo, &NPCActions, &AnimationHandler)>,
-            Option<&Shaymin>,
+            Option<(&NPCInfo, &NPCActions, &AnimationHandler, &BehaviourTree)>,
+            Has<Shaymin>,
             &GlobalTransform,
         ),
         Or<(With<NPCInfo>, With<Shaymin>)>,
     >,
-    moves: Query<&Moves>,
+    moves: Query<(&Moves, Option<&MoveCooldowns>, Option<&PowerPoints>)>,
     move_list: Option<Res<MoveList>>,
     move_data: Res<Assets<MoveData>>,
+    statuses: Query<&StatusCondition>,
+    healths: Query<&Health>,
+    perceptions: Query<&Perception>,
+    tile_query: TileQuery,
+    tiles: TileSurfaces,
     mut query2: Query<&mut NPCDesicion>,
 ) {
     let Some(move_list) = move_list else {
         return;
     };
-    for (entity, npc, player, gtransform) in &query {
-        let Some((npc, npc_actions, anim)) = npc else {
+    let line_of_sight = |from, to| behaviour::line_of_sight(&tile_query, &tiles, from, to);
+
+    for (entity, npc, _, gtransform) in &query {
+        let Some((npc, npc_actions, anim, tree)) = npc else {
             continue;
         };
 
+        // sleeping NPCs can't decide to do anything until they wake up
+        if statuses
+            .get(entity)
+            .is_ok_and(|status| status.kind.prevents_action())
+        {
+            if let Ok(mut desicion) = query2.get_mut(entity) {
+                *desicion = NPCDesicion::Idle;
+            }
+            continue;
+        }
+
         // blocking animations shouldnt let them do anything anyways, so skip now to save on the extra work
-        if match anim.animation_data() {
-            Some(some) => some.is_blocking(),
-            None => false,
-        } {
+        // unless the animation is in a cancel window, where they can try to combo into another move
+        let chaining = anim.is_blocking();
+        if chaining && !anim.can_cancel() {
             continue;
         }
-        let result = match *npc_actions {
-            NPCActions::Offensive { focus }
-                if !can_aggro(npc, {
-                    let get = query.get(focus).unwrap();
-                    get.1
-                        .map(|npc| npc.0)
-                        .or(get.2.map(|_| &NPCInfo::Team {}))
-                        .unwrap()
-                }) =>
-            {
-                NPCDesicion::SetAnimation(AnimType::Idle)
-            }
-            NPCActions::Offensive { focus } => {
-                let (_, _, _, other_gtransform) = query.get(focus).unwrap();
-
-                let distance = Vec3::from((
-                    other_gtransform.translation().xz() - gtransform.translation().xz(),
-                    0.0,
-                ));
-
-                if let Some((move_id, range)) =
-                    moves.get(entity).unwrap().iter().find_map(|move_id| {
-                        let move_data =
-                            move_data.get(move_list.data.get(move_id).unwrap()).unwrap();
-
-                        move_data
-                            .extra_info
-                            .get("range")
-                            .and_then(|range| range.clone().into_rust::<f32>().ok())
-                            .map(|range| (*move_id, range))
+
+        let hostile = |other: Entity| {
+            query.get(other).is_ok_and(|(_, other_npc, is_player, _)| {
+                let other_npc = match other_npc {
+                    Some((other_npc, ..)) => other_npc,
+                    None if is_player => &NPCInfo::Team {},
+                    None => return false,
+                };
+                can_aggro(npc, other_npc)
+            })
+        };
+
+        let target = match *npc_actions {
+            NPCActions::Offensive { focus } if hostile(focus) => query
+                .get(focus)
+                .ok()
+                .map(|(.., other_gtransform)| other_gtransform.translation()),
+            _ => None,
+        };
+
+        // with no moves ready the tree falls through to moving, so paralysed NPCs keep their distance
+        let can_attack = statuses
+            .get(entity)
+            .ok()
+            .is_none_or(|status| !status.kind.prevents_moves());
+        let ready_moves = moves
+            .get(entity)
+            .ok()
+            .filter(|_| can_attack)
+            .map(|(moves, cooldowns, pp)| {
+                moves
+                    .iter()
+                    .filter_map(|move_id| {
+                        let move_data = move_data.get(move_list.data.get(move_id)?)?;
+                        move_ready(*move_id, move_data, cooldowns, pp)
+                            .then_some((*move_id, move_data.range))
                     })
-                {
-                    if distance.length_squared() <= range.powi(2) {
-                        NPCDesicion::BasicAttack {
-                            direction: Dir2::new(distance.normalize_or_zero().xy()).ok(),
-                            move_id,
-                        }
-                    } else {
-                        NPCDesicion::Move {
-                            target: distance.normalize_or_zero().xzy(),
-                        }
-                    }
-                } else {
-                    NPCDesicion::Move {
-                        target: distance.normalize_or_zero().xzy(),
-                    }
-                }
-            }
-            NPCActions::Defensive => {
-                let mut distance = Vec3::ZERO;
-
-                for (_, other_npc, player, other_gtransform) in &query {
-                    let other = match (other_npc, player) {
-                        (Some(npc), None) => npc.0,
-                        (None, Some(player)) => &NPCInfo::Team {},
-                        (Some(npc), Some(_)) => npc.0,
-                        (None, None) => todo!(),
-                    };
-                    if can_aggro(npc, other) {
-                        distance += gtransform.translation() - other_gtransform.translation();
-                    }
-                }
+                    .collect()
+            })
+            .unwrap_or_default();
 
-                distance = distance.normalize_or_zero();
+        // only what the NPC could notice is worth reacting to
+        let aware = perceptions.get(entity).map_or(f32::INFINITY, |perception| {
+            perception.sight_radius.max(perception.hearing_radius)
+        });
+        let position = gtransform.translation();
+        let context = BehaviourContext {
+            position,
+            target,
+            hostiles: query
+                .iter()
+                .filter(|(other, ..)| hostile(*other))
+                .map(|(.., other_gtransform)| other_gtransform.translation())
+                .filter(|other| other.xz().distance(position.xz()) <= aware)
+                .collect(),
+            health_ratio: healths.get(entity).map_or(1.0, Health::ratio),
+            ready_moves,
+            line_of_sight: &line_of_sight,
+        };
 
-                NPCDesicion::Move { target: distance }
-            }
-            NPCActions::Idle { explore } => {
-                if explore != Vec3::ZERO {
-                    NPCDesicion::Move { target: explore }
-                } else {
-                    NPCDesicion::Idle
-                }
-            }
+        let mut result = match *npc_actions {
+            NPCActions::Return { home } => NPCDesicion::MoveTo { destination: home },
+            _ => tree.decide(&context).unwrap_or_default(),
         };
 
+        // only attacks can cancel the current animation
+        if chaining && !matches!(result, NPCDesicion::BasicAttack { .. }) {
+            result = NPCDesicion::Idle;
+        }
+
         let Ok(mut desicion) = query2.get_mut(entity) else {
             // commands.entity(entity).insert(result);
             continue;
@@ -175,18 +205,33 @@ pub(crate) fn commit_npc_actions(
         &mut AnimationHandler,
         &mut FacingDirection,
         &mut Transform,
+        Option<&StatusCondition>,
     )>,
     time: Res<Time>,
 ) {
-    for (entity, info, desicion, mut anim, mut facing, mut transform) in &mut query {
+    for (entity, info, desicion, mut anim, mut facing, mut transform, status) in &mut query {
         match desicion.clone() {
             NPCDesicion::Idle => (),
             NPCDesicion::Move { target: direction } => {
-                transform.translation += direction * time.delta_secs();
+                let speed = status.map_or(1.0, |status| status.kind.speed_multiplier());
+                transform.translation += direction * speed * time.delta_secs();
+                if let Ok(dir) = Dir2::new(direction.xz()) {
+                    facing.set(dir);
+                }
+            }
+            NPCDesicion::MoveTo { destination } => {
+                // only reached if nothing found a path, so head straight there
+                let direction = (destination - transform.translation)
+                    .with_y(0.0)
+                    .normalize_or_zero();
+                let speed = status.map_or(1.0, |status| status.kind.speed_multiplier());
+                transform.translation += direction * speed * time.delta_secs();
             }
             NPCDesicion::BasicAttack { direction, move_id } => {
-                if let Some(data) = anim.animation_data()
-                    && !data.is_blocking()
+                let able = status.is_none_or(|status| !status.kind.prevents_moves());
+                if able
+                    && anim.animation_data().is_some()
+                    && (!anim.is_blocking() || anim.can_cancel())
                 {
                     // if let Some(direction) = direction {
                     //     anim.update_direction(direction);
@@ -199,6 +244,7 @@ pub(crate) fn commit_npc_actions(
                     commands.queue(SpawnMove {
                         move_id,
                         parent: entity,
+                        charge: 0,
                     })
                 }
             }
@@ -210,11 +256,8 @@ pub(crate) fn commit_npc_actions(
 }
 
 fn can_aggro(npc: &NPCInfo, other_npc: &NPCInfo) -> bool {
-    if matches!(npc, NPCInfo::Enemy { .. }) {
-        matches!(other_npc, NPCInfo::Team { .. })
-    } else if matches!(npc, NPCInfo::Team { .. }) {
-        matches!(other_npc, NPCInfo::Enemy { .. })
-    } else {
-        false
+    match (npc.faction(), other_npc.faction()) {
+        (Some(faction), Some(other_faction)) => faction != other_faction,
+        _ => false,
     }
 }
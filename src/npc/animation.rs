@@ -2,7 +2,7 @@ use super::NPC;
 use super::stats::FacingDirection;
 use crate::animation::{AnimType, AnimationData, AnimationDirLabel};
 use crate::assets::AnimationSpritesheet;
-use crate::moves::interfaces::MoveInfo;
+use crate::moves::lifecycle::{EndMove, MoveAnimation};
 use crate::shaymin::{ClientQuery, Shaymin, SpriteChildMarker};
 use crate::sprite3d::Sprite3d;
 use bevy::color::palettes;
@@ -26,6 +26,10 @@ pub(crate) struct AnimationHandler {
     pub animations: HashMap<AnimType, AnimationData>,
     pub spritesheet: AnimationSpritesheet,
     pub looping: bool,
+    /// Set while something like a status condition holds the animation in place. See [`Self::lock`].
+    locked: bool,
+    /// Seconds left that the timer is frozen for. See [`Self::hit_stop`].
+    frozen: f32,
 }
 
 #[derive(Debug, Error)]
@@ -57,10 +61,17 @@ impl AnimationHandler {
             frame: 0.0,
             looping: false,
             speed: 4.0,
+            locked: false,
+            frozen: 0.0,
         }
     }
 
     pub fn update(&mut self, delta: f32) -> bool {
+        if self.frozen > 0.0 {
+            self.frozen -= delta;
+            return false;
+        }
+
         let Some(animation_data) = self.animations.get(&self.current) else {
             log::error!("Could not find animation data for {:?}", self.current);
             self.start_animation(AnimType::Idle);
@@ -122,18 +133,10 @@ impl AnimationHandler {
         Ok((index, flip))
     }
 
-    pub fn frame(&self) -> f32 {
-        self.frame * self.speed
-    }
-
     pub fn time(&self) -> f32 {
         self.frame
     }
 
-    pub fn speed(&self) -> f32 {
-        self.speed
-    }
-
     pub fn current(&self) -> AnimType {
         self.current
     }
@@ -142,30 +145,94 @@ impl AnimationHandler {
         self.spritesheet.data.0.get(&self.current)
     }
 
-    pub fn get_animation_data(&self) -> Option<&AnimationData> {
-        self.spritesheet.data.0.get(&self.current)
-    }
-
     /// Should only be called if [`AnimationData::is_blocking`] is false
+    ///
+    /// Does nothing while the handler is locked.
     pub fn start_animation(&mut self, animation: AnimType) {
+        if self.locked {
+            return;
+        }
         self.looping = false;
         self.frame = 0.0;
         self.current = animation;
     }
+
+    /// Loops `animation` and ignores [`Self::start_animation`] until [`Self::unlock`] is called.
+    ///
+    /// Falls back to [`AnimType::Idle`] if this handler has no data for `animation`.
+    pub fn lock(&mut self, animation: AnimType) {
+        let animation = if self.animations.contains_key(&animation) {
+            animation
+        } else {
+            AnimType::Idle
+        };
+        self.locked = false;
+        self.start_animation(animation);
+        self.looping = true;
+        self.locked = true;
+    }
+
+    /// Starts `animation` only if [`AnimType::can_interrupt`] allows it to cut the current one short.
+    ///
+    /// Returns `true` if the animation was started.
+    pub fn interrupt(&mut self, animation: AnimType) -> bool {
+        if self.locked
+            || !self.animations.contains_key(&animation)
+            || !self.current.can_interrupt(Some(animation))
+        {
+            return false;
+        }
+        self.start_animation(animation);
+        true
+    }
+
+    /// Freezes the timer for `duration` seconds, such as for the impact of a hit.
+    pub fn hit_stop(&mut self, duration: f32) {
+        self.frozen = self.frozen.max(duration);
+    }
+
+    pub fn unlock(&mut self) {
+        self.locked = false;
+        self.start_animation(AnimType::Idle);
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /// Returns `true` if the current animation is inside one of its cancel windows,
+    /// so a new move can be chained even though it [`Self::is_blocking`].
+    pub fn can_cancel(&self) -> bool {
+        !self.locked
+            && self
+                .animation_data()
+                .is_some_and(|data| data.can_cancel(self.frame))
+    }
+
+    /// Returns `true` if the current animation shouldn't be interrupted by new actions.
+    pub fn is_blocking(&self) -> bool {
+        self.locked || self.animation_data().is_some_and(|data| data.is_blocking())
+    }
 }
 
+/// Ends the moves of every NPC whose animation finished, or changed to something else.
 pub(super) fn update_anim_handler_timer(
     mut commands: Commands,
     mut npcs: Query<(&mut AnimationHandler, &Children)>,
-    move_query: Query<&MoveInfo>,
+    move_query: Query<&MoveAnimation>,
     delta: Res<Time>,
 ) {
     for (mut anim, children) in &mut npcs {
-        if anim.update(delta.delta_secs()) {
-            for child in children.iter() {
-                if move_query.get(child).is_ok() {
-                    commands.entity(child).despawn();
-                }
+        let finished = anim.update(delta.delta_secs());
+        for child in children.iter() {
+            let Ok(move_animation) = move_query.get(child) else {
+                continue;
+            };
+            if finished || anim.current() != **move_animation {
+                commands.queue(EndMove {
+                    move_entity: child,
+                    interrupted: !finished,
+                });
             }
         }
     }
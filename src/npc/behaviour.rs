@@ -0,0 +1,329 @@
+use super::ai::NPCDesicion;
+use crate::animation::AnimType;
+use crate::ldtk::{TileQuery, TileSurfaces, tile_cell};
+use crate::moves::Move;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A behaviour tree, as set in [`NPCData::behaviour`], that decides what an NPC does every frame.
+///
+/// Nodes either succeed or fail. The first [`BehaviourAction`] that succeeds on the way
+/// becomes the NPC's [`NPCDesicion`], and the NPC stays idle if the whole tree fails.
+///
+/// [`NPCData::behaviour`]: super::file::NPCData::behaviour
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum Behaviour {
+    /// Runs each child in order until one succeeds, failing if none do.
+    Selector(Vec<Behaviour>),
+    /// Runs each child in order until one fails, succeeding if none do.
+    Sequence(Vec<Behaviour>),
+    /// Succeeds if the child fails, and fails if it succeeds.
+    Invert(Box<Behaviour>),
+    /// Succeeds if the condition holds.
+    Condition(BehaviourCondition),
+    /// Succeeds if the action can be done.
+    Action(BehaviourAction),
+}
+
+/// Attacks the target with any move that can reach it, or chases it otherwise.
+impl Default for Behaviour {
+    fn default() -> Self {
+        Behaviour::Selector(vec![
+            Behaviour::Action(BehaviourAction::Attack(None)),
+            Behaviour::Action(BehaviourAction::Chase),
+        ])
+    }
+}
+
+/// Something a [`Behaviour::Condition`] checks about the NPC or its target.
+///
+/// Every condition about the target fails if the NPC has none.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum BehaviourCondition {
+    /// The target is at least `min` and at most `max` away, ignoring height.
+    Distance {
+        #[serde(default)]
+        min: Option<f32>,
+        #[serde(default)]
+        max: Option<f32>,
+    },
+    /// The NPC's health, as a fraction of its maximum, is at least `min` and at most `max`.
+    Health {
+        #[serde(default)]
+        min: Option<f32>,
+        #[serde(default)]
+        max: Option<f32>,
+    },
+    /// The move can be used right now, or any of the NPC's moves if not set.
+    MoveReady(Option<Move>),
+    /// No terrain rises between the NPC and its target.
+    LineOfSight,
+}
+
+/// Something a [`Behaviour::Action`] has the NPC do.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum BehaviourAction {
+    /// Does nothing on purpose. Always succeeds.
+    Idle,
+    /// Plays an animation. Always succeeds.
+    Animate(AnimType),
+    /// Walks towards the target, finding a way around anything in the way.
+    Chase,
+    /// Walks away from everything it's hostile to, most of all whatever is closest.
+    /// Fails if there is nothing to run from.
+    Flee,
+    /// Uses a move on the target, or whichever of its moves can reach if not set.
+    ///
+    /// Fails unless the move is ready and the target is within its range.
+    Attack(Option<Move>),
+}
+
+/// Everything about an NPC's surroundings that its [`Behaviour`] can look at.
+pub(crate) struct BehaviourContext<'a> {
+    pub position: Vec3,
+    pub target: Option<Vec3>,
+    /// The positions of everything the NPC is hostile to and close enough to perceive.
+    pub hostiles: Vec<Vec3>,
+    pub health_ratio: f32,
+    /// The NPC's moves that are ready to use, and how far they reach.
+    pub ready_moves: Vec<(Move, Option<f32>)>,
+    pub line_of_sight: &'a dyn Fn(Vec3, Vec3) -> bool,
+}
+
+impl BehaviourContext<'_> {
+    /// The offset from the NPC to its target, flattened onto the ground.
+    fn to_target(&self) -> Option<Vec3> {
+        self.target
+            .map(|target| (target - self.position).with_y(0.0))
+    }
+}
+
+fn within(value: f32, min: Option<f32>, max: Option<f32>) -> bool {
+    min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+}
+
+impl Behaviour {
+    /// Runs the tree, returning the decision of the first action that succeeded.
+    pub(crate) fn decide(&self, context: &BehaviourContext) -> Option<NPCDesicion> {
+        let mut desicion = None;
+        self.run(context, &mut desicion);
+        desicion
+    }
+
+    fn run(&self, context: &BehaviourContext, desicion: &mut Option<NPCDesicion>) -> bool {
+        match self {
+            Behaviour::Selector(children) => {
+                children.iter().any(|child| child.run(context, desicion))
+            }
+            Behaviour::Sequence(children) => {
+                // a sequence that fails halfway through shouldn't leave its earlier actions behind
+                let before = desicion.clone();
+                let success = children.iter().all(|child| child.run(context, desicion));
+                if !success {
+                    *desicion = before;
+                }
+                success
+            }
+            Behaviour::Invert(child) => {
+                let before = desicion.clone();
+                let success = child.run(context, desicion);
+                *desicion = before;
+                !success
+            }
+            Behaviour::Condition(condition) => condition.check(context),
+            Behaviour::Action(action) => match action.act(context) {
+                Some(result) => {
+                    desicion.get_or_insert(result);
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+}
+
+impl BehaviourCondition {
+    fn check(&self, context: &BehaviourContext) -> bool {
+        match *self {
+            BehaviourCondition::Distance { min, max } => context
+                .to_target()
+                .is_some_and(|offset| within(offset.length(), min, max)),
+            BehaviourCondition::Health { min, max } => within(context.health_ratio, min, max),
+            BehaviourCondition::MoveReady(move_id) => context
+                .ready_moves
+                .iter()
+                .any(|(ready, _)| move_id.is_none_or(|move_id| move_id == *ready)),
+            BehaviourCondition::LineOfSight => context
+                .target
+                .is_some_and(|target| (context.line_of_sight)(context.position, target)),
+        }
+    }
+}
+
+impl BehaviourAction {
+    fn act(&self, context: &BehaviourContext) -> Option<NPCDesicion> {
+        match *self {
+            BehaviourAction::Idle => Some(NPCDesicion::Idle),
+            BehaviourAction::Animate(anim_type) => Some(NPCDesicion::SetAnimation(anim_type)),
+            BehaviourAction::Chase => context
+                .target
+                .map(|destination| NPCDesicion::MoveTo { destination }),
+            BehaviourAction::Flee => {
+                // each hostile pushes harder the closer it is, so the nearest one matters most
+                let away = context
+                    .hostiles
+                    .iter()
+                    .map(|hostile| (context.position - *hostile).with_y(0.0))
+                    .filter(|offset| *offset != Vec3::ZERO)
+                    .map(|offset| offset / offset.length_squared())
+                    .sum::<Vec3>()
+                    .normalize_or_zero();
+                (away != Vec3::ZERO).then_some(NPCDesicion::Move { target: away })
+            }
+            BehaviourAction::Attack(move_id) => {
+                let offset = context.to_target()?;
+                let distance = offset.length();
+                let (move_id, _) = context.ready_moves.iter().find(|(ready, range)| {
+                    match move_id {
+                        Some(move_id) => {
+                            move_id == *ready && range.is_none_or(|range| distance <= range)
+                        }
+                        // only moves with a range are picked on their own,
+                        // since there is no telling when the others are worth using
+                        None => range.is_some_and(|range| distance <= range),
+                    }
+                })?;
+                Some(NPCDesicion::BasicAttack {
+                    direction: Dir2::new(offset.xz()).ok(),
+                    move_id: *move_id,
+                })
+            }
+        }
+    }
+}
+
+/// How far above their feet NPCs see from.
+const EYE_HEIGHT: f32 = 0.5;
+
+/// How far apart the points checked along a line of sight are.
+const SIGHT_STEP: f32 = 0.25;
+
+/// Returns `true` if no tile between `from` and `to` rises above the line between them,
+/// as seen from [`EYE_HEIGHT`] above both.
+pub(crate) fn line_of_sight(
+    tile_query: &TileQuery,
+    tiles: &TileSurfaces,
+    from: Vec3,
+    to: Vec3,
+) -> bool {
+    let from = from + Vec3::Y * EYE_HEIGHT;
+    let to = to + Vec3::Y * EYE_HEIGHT;
+    let steps = (from.xz().distance(to.xz()) / SIGHT_STEP).ceil() as u32;
+
+    (1..steps).all(|step| {
+        let point = from.lerp(to, step as f32 / steps as f32);
+        tile_query
+            .surface_height(tiles, tile_cell(point), point.xz())
+            .is_none_or(|height| height <= point.y)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn always_visible(_: Vec3, _: Vec3) -> bool {
+        true
+    }
+
+    fn context(target: Option<Vec3>, hostiles: Vec<Vec3>) -> BehaviourContext<'static> {
+        BehaviourContext {
+            position: Vec3::ZERO,
+            target,
+            hostiles,
+            health_ratio: 1.0,
+            ready_moves: Vec::new(),
+            line_of_sight: &always_visible,
+        }
+    }
+
+    fn animate(anim_type: AnimType) -> Behaviour {
+        Behaviour::Action(BehaviourAction::Animate(anim_type))
+    }
+
+    fn near_target() -> Behaviour {
+        Behaviour::Condition(BehaviourCondition::Distance {
+            min: None,
+            max: Some(1.0),
+        })
+    }
+
+    #[test]
+    fn selector_takes_first_success() {
+        let tree = Behaviour::Selector(vec![
+            Behaviour::Action(BehaviourAction::Chase),
+            animate(AnimType::Walking),
+            animate(AnimType::Hurt),
+        ]);
+        // nothing to chase, so it falls through to the first animation
+        assert!(matches!(
+            tree.decide(&context(None, Vec::new())),
+            Some(NPCDesicion::SetAnimation(AnimType::Walking))
+        ));
+        assert!(
+            Behaviour::Selector(Vec::new())
+                .decide(&context(None, Vec::new()))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn failed_sequence_rolls_back() {
+        let tree = Behaviour::Selector(vec![
+            Behaviour::Sequence(vec![animate(AnimType::Walking), near_target()]),
+            animate(AnimType::Hurt),
+        ]);
+        let far = context(Some(Vec3::X * 5.0), Vec::new());
+        assert!(matches!(
+            tree.decide(&far),
+            Some(NPCDesicion::SetAnimation(AnimType::Hurt))
+        ));
+        let near = context(Some(Vec3::X * 0.5), Vec::new());
+        assert!(matches!(
+            tree.decide(&near),
+            Some(NPCDesicion::SetAnimation(AnimType::Walking))
+        ));
+    }
+
+    #[test]
+    fn invert_flips_and_discards() {
+        let far = context(Some(Vec3::X * 5.0), Vec::new());
+        let tree = Behaviour::Sequence(vec![
+            Behaviour::Invert(Box::new(near_target())),
+            animate(AnimType::Walking),
+        ]);
+        assert!(matches!(
+            tree.decide(&far),
+            Some(NPCDesicion::SetAnimation(AnimType::Walking))
+        ));
+        // a child that succeeds makes the invert fail, without keeping its decision
+        let tree = Behaviour::Invert(Box::new(animate(AnimType::Walking)));
+        assert!(tree.decide(&far).is_none());
+    }
+
+    #[test]
+    fn flee_from_closest() {
+        let hostiles = vec![Vec3::X, Vec3::NEG_X * 10.0];
+        let Some(NPCDesicion::Move { target }) =
+            Behaviour::Action(BehaviourAction::Flee).decide(&context(None, hostiles))
+        else {
+            panic!("fleeing with hostiles around should move");
+        };
+        assert!(target.x < 0.0);
+        assert!(
+            Behaviour::Action(BehaviourAction::Flee)
+                .decide(&context(None, Vec::new()))
+                .is_none()
+        );
+    }
+}
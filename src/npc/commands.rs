@@ -1,14 +1,20 @@
 use super::animation::AnimationHandler;
-use super::{NPC, NPCInfo, animation, file::NPCAlmanac, file::NPCData};
+use super::{NPC, NPCInfo, animation, file::NPCAlmanac, file::NPCData, status};
 use crate::billboard::Billboard;
 use crate::collision::physics::Rigidbody;
 use crate::collision::{
     self, BasicCollider, ColliderShape, CollisionLayers, DynamicCollision, TilemapCollision,
     ZHitbox,
 };
-use crate::npc::ai::{NPCActions, NPCDesicion};
-use crate::npc::stats::FacingDirection;
-use crate::shaymin::{self, Shaymin};
+use crate::dialogue::Dialogue;
+use crate::npc::ai::{BehaviourTree, NPCActions, NPCDesicion};
+use crate::npc::companion::JoinParty;
+use crate::npc::navigation::NavPath;
+use crate::npc::patrol::Patrol;
+use crate::npc::perception::Home;
+use crate::npc::spawner::SpawnedBy;
+use crate::npc::squad::{Squad, SquadMember};
+use crate::npc::stats::{FacingDirection, Health};
 use crate::sprite3d::{Sprite3d, Sprite3dBuilder, Sprite3dParams};
 use bevy::ecs::system::SystemState;
 use bevy::prelude::*;
@@ -20,6 +26,15 @@ pub struct SpawnNPC {
     pub npc_id: NPC,
     pub position: Vec3,
     pub name: Option<String>,
+    /// Overrides [`NPCData::squad`] if set.
+    pub squad: Option<String>,
+    pub patrol: Option<Patrol>,
+    /// The [`Spawner`](super::spawner::Spawner) the NPC came from, if any.
+    pub spawner: Option<Entity>,
+    /// Overrides [`NPCData::dialogue`] if set.
+    pub dialogue: Option<Dialogue>,
+    /// Has the NPC [`JoinParty`] once it's spawned. Only works for [`NPCInfo::Team`] NPCs.
+    pub party: bool,
 }
 
 impl Command for SpawnNPC {
@@ -60,16 +75,16 @@ impl Command for SpawnNPC {
             Transform::from_translation(self.position)
                 .with_rotation(Quat::from_rotation_x(f32::to_radians(-90.0))),
             Name::new(self.name.clone().unwrap_or(data.display_name.clone())),
+            data.typing.clone(),
         );
         let animation = (
             AnimationHandler::new(data.spritesheet.clone()),
-            NPCActions::Offensive {
-                focus: world
-                    .query_filtered::<Entity, With<Shaymin>>()
-                    .single(&world)
-                    .unwrap(),
-            },
+            NPCActions::default(),
+            data.perception.clone(),
+            Home(self.position),
             NPCDesicion::default(),
+            BehaviourTree(data.behaviour.clone()),
+            NavPath::default(),
             FacingDirection::default(),
             Rigidbody::default(),
             TilemapCollision,
@@ -82,7 +97,10 @@ impl Command for SpawnNPC {
         };
         let npcinfo = data.info.clone();
         let moves = data.moves.clone();
+        let pp = data.pp.clone();
         let stats = data.stats.clone();
+        let squad = self.squad.clone().or(data.squad.clone());
+        let dialogue = self.dialogue.or(data.dialogue);
 
         // Construct a `SystemState` struct, passing in a tuple of `SystemParam`
         // as if you were writing an ordinary system.
@@ -114,7 +132,10 @@ impl Command for SpawnNPC {
                     true,
                     shape,
                     CollisionLayers::NPC,
-                    CollisionLayers::Wall | CollisionLayers::NPC | CollisionLayers::Projectile,
+                    CollisionLayers::Wall
+                        | CollisionLayers::NPC
+                        | CollisionLayers::Projectile
+                        | CollisionLayers::Attack,
                 ),
                 ZHitbox {
                     y_tolerance: 0.5,
@@ -126,18 +147,41 @@ impl Command for SpawnNPC {
             observe_collision = true;
         }
 
+        if let Some(dialogue) = dialogue {
+            entity.insert(dialogue);
+        }
+
+        if let Some(spawner) = self.spawner {
+            entity.insert(SpawnedBy(spawner));
+        }
+
+        if let Some(patrol) = self.patrol {
+            entity.insert(patrol);
+        }
+
+        if let Some(squad) = squad {
+            entity.insert((Squad(squad), SquadMember::default()));
+        }
+
         if let Some(moves) = moves {
             entity.insert(moves);
         }
 
+        if let Some(mut pp) = pp {
+            pp.restore_all();
+            entity.insert(pp);
+        }
+
         match (npcinfo, stats) {
             (NPCInfo::None, _) => (),
             (NPCInfo::Silent, _) => {
                 entity.insert(animation);
             }
             (NPCInfo::Enemy { .. }, Some(stats)) | (NPCInfo::Team { .. }, Some(stats)) => {
+                let (health, damage) = stats;
                 entity.insert(animation);
-                entity.insert(stats);
+                // data files only give the starting hp, which is also the most it can have
+                entity.insert((Health::new(health.hp), damage));
                 has_stats = true;
             }
             _ => panic!("Invalid NPC configuration (Missing stats in {:#?})", name),
@@ -149,8 +193,14 @@ impl Command for SpawnNPC {
         }
         if has_stats {
             entity.observe(collision::physics::take_hits);
+            entity.observe(status::catch_status);
         }
 
+        let id = entity.id();
         log::info!("Spawned NPC: {}", name);
+
+        if self.party {
+            JoinParty(id).apply(world);
+        }
     }
 }
@@ -0,0 +1,162 @@
+use super::ai::{NPCActions, NPCDesicion};
+use super::navigation::NavPath;
+use super::perception::Home;
+use super::stats::FacingDirection;
+use super::{Faction, NPCInfo};
+use crate::collision::physics::Rigidbody;
+use crate::shaymin::Shaymin;
+use bevy::prelude::*;
+
+/// Makes an NPC a companion of the player it points to, following them around and helping them fight.
+///
+/// Added by [`JoinParty`], or by the `Party` field of an NPC placed in LDtk, and taken away by [`LeaveParty`].
+/// Both can be done by talking to the NPC, through [`DialogueAction`] choices.
+///
+/// [`DialogueAction`]: crate::dialogue::DialogueAction
+#[derive(Debug, Component, Reflect)]
+#[relationship(relationship_target = Party)]
+pub struct PartyMember(pub Entity);
+
+/// Every companion currently following the player.
+#[derive(Debug, Component, Reflect, Default)]
+#[relationship_target(relationship = PartyMember)]
+pub struct Party(Vec<Entity>);
+
+/// Adds an [`NPCInfo::Team`] NPC to the player's party.
+pub struct JoinParty(pub Entity);
+
+impl Command for JoinParty {
+    fn apply(self, world: &mut World) {
+        if !matches!(world.get::<NPCInfo>(self.0), Some(NPCInfo::Team { .. })) {
+            log::warn!(
+                "{} is not on the player's team, so it can't join the party",
+                self.0
+            );
+            return;
+        }
+        let Ok(leader) = world
+            .query_filtered::<Entity, With<Shaymin>>()
+            .single(world)
+        else {
+            log::warn!("There is no player for {} to follow", self.0);
+            return;
+        };
+        world.entity_mut(self.0).insert(PartyMember(leader));
+    }
+}
+
+/// Takes an NPC out of the player's party, leaving it wherever it is.
+pub struct LeaveParty(pub Entity);
+
+impl Command for LeaveParty {
+    fn apply(self, world: &mut World) {
+        let Ok(mut entity) = world.get_entity_mut(self.0) else {
+            return;
+        };
+        entity.remove::<PartyMember>();
+        // stay put instead of heading back to where the player last was
+        if let Some(position) = entity
+            .get::<Transform>()
+            .map(|transform| transform.translation)
+            && let Some(mut home) = entity.get_mut::<Home>()
+        {
+            home.0 = position;
+        }
+    }
+}
+
+/// How far behind the player companions try to stay.
+const TRAIL_DISTANCE: f32 = 1.5;
+
+/// How far behind companions can fall before they warp back to the player.
+const WARP_DISTANCE: f32 = 16.0;
+
+/// How close to the player an enemy has to be for companions to go after it.
+const ASSIST_RADIUS: f32 = 5.0;
+
+/// Sends companions after the closest enemy near the player, and keeps their leash on the player.
+///
+/// Companions don't head home after giving up a fight, they go back to following.
+pub(crate) fn assist_leader(
+    mut companions: Query<(&PartyMember, &mut NPCActions, &mut Home, &NPCInfo)>,
+    leaders: Query<&GlobalTransform, With<Shaymin>>,
+    others: Query<(Entity, Option<&NPCInfo>, Has<Shaymin>, &GlobalTransform)>,
+) {
+    for (member, mut actions, mut home, info) in &mut companions {
+        let Ok(leader) = leaders.get(member.0).map(GlobalTransform::translation) else {
+            continue;
+        };
+        home.0 = leader;
+
+        if matches!(*actions, NPCActions::Return { .. }) {
+            *actions = NPCActions::default();
+        }
+        if !matches!(*actions, NPCActions::Idle { .. }) {
+            continue;
+        }
+
+        let faction = info.faction();
+        let enemy = others
+            .iter()
+            .filter(|(_, other_info, is_player, _)| {
+                let other = Faction::of(*other_info, *is_player);
+                faction.is_some() && other.is_some() && other != faction
+            })
+            .map(|(entity, .., transform)| (entity, transform.translation()))
+            .filter(|(_, position)| position.xz().distance(leader.xz()) <= ASSIST_RADIUS)
+            .min_by(|(_, a), (_, b)| {
+                a.distance_squared(leader)
+                    .total_cmp(&b.distance_squared(leader))
+            });
+
+        if let Some((focus, _)) = enemy {
+            *actions = NPCActions::Offensive { focus };
+        }
+    }
+}
+
+/// Walks idle companions after the player, and warps them back if they fell too far behind.
+///
+/// Only takes over if their behaviour didn't find anything else to do.
+pub(crate) fn follow_leader(
+    mut companions: Query<(
+        &PartyMember,
+        &NPCActions,
+        &mut NPCDesicion,
+        &mut NavPath,
+        &mut Transform,
+        Option<&mut Rigidbody>,
+    )>,
+    leaders: Query<(&GlobalTransform, &FacingDirection), With<Shaymin>>,
+) {
+    for (member, actions, mut desicion, mut path, mut transform, rigidbody) in &mut companions {
+        let Ok((leader, facing)) = leaders.get(member.0) else {
+            continue;
+        };
+        let leader = leader.translation();
+        let distance = transform.translation.xz().distance(leader.xz());
+
+        if distance > WARP_DISTANCE {
+            transform.translation = leader - facing.extend(0.0).xzy() * TRAIL_DISTANCE;
+            // whatever it was standing on or moving with is back where it came from
+            if let Some(mut rigidbody) = rigidbody {
+                *rigidbody = Rigidbody {
+                    previous_position: transform.translation,
+                    ..default()
+                };
+            }
+            *path = NavPath::default();
+            *desicion = NPCDesicion::Idle;
+            continue;
+        }
+
+        if !matches!(actions, NPCActions::Idle { .. }) || !matches!(*desicion, NPCDesicion::Idle) {
+            continue;
+        }
+        if distance > TRAIL_DISTANCE {
+            *desicion = NPCDesicion::MoveTo {
+                destination: leader,
+            };
+        }
+    }
+}
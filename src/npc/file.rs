@@ -1,11 +1,15 @@
 use super::NPC;
 use super::NPCInfo;
+use super::behaviour::Behaviour;
+use super::perception::Perception;
 use super::stats::Damage;
 use super::stats::Health;
 use crate::assets::AnimationSpritesheet;
 use crate::collision::{BasicCollider, ColliderShape};
+use crate::dialogue::Dialogue;
 use crate::moves::Move;
-use crate::moves::interfaces::Moves;
+use crate::moves::interfaces::{Moves, PowerPoints};
+use crate::typing::Typing;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 use bevy_asset_loader::asset_collection::AssetCollection;
@@ -32,6 +36,21 @@ pub(crate) struct NPCData {
     pub(crate) stats: Option<(Health, Damage)>,
     #[serde(default)]
     pub(crate) moves: Option<Moves>,
+    #[serde(default)]
+    pub(crate) pp: Option<PowerPoints>,
+    #[serde(default)]
+    pub(crate) typing: Typing,
+    /// How this NPC decides what to do, when it fights.
+    #[serde(default)]
+    #[reflect(ignore)]
+    pub(crate) behaviour: Behaviour,
+    #[serde(default)]
+    pub(crate) perception: Perception,
+    /// The [`Squad`](super::squad::Squad) this NPC fights alongside, unless placed in a different one.
+    #[serde(default)]
+    pub(crate) squad: Option<String>,
+    #[serde(default)]
+    pub(crate) dialogue: Option<Dialogue>,
 }
 
 pub(crate) fn validate_npc_data(
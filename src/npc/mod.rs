@@ -12,9 +12,17 @@ use crate::moves::Move;
 
 pub mod ai;
 pub mod animation;
+pub mod behaviour;
 pub mod commands;
+pub mod companion;
 pub mod file;
+pub mod navigation;
+pub mod patrol;
+pub mod perception;
+pub mod spawner;
+pub mod squad;
 pub mod stats;
+pub mod status;
 
 pub struct NPCPlugin;
 
@@ -23,6 +31,9 @@ impl Plugin for NPCPlugin {
         app
             //
             .add_event::<OnDead>()
+            .add_event::<spawner::EncounterCleared>()
+            .init_resource::<spawner::Encounters>()
+            .register_type::<status::StatusCondition>()
             .add_systems(FixedLast, stats::query_dead)
             .add_systems(Update, stats::remove_dead)
             .add_systems(
@@ -35,7 +46,35 @@ impl Plugin for NPCPlugin {
             )
             .add_systems(
                 FixedUpdate,
-                (ai::run_enemy_npc_ai, ai::commit_npc_actions).chain(),
+                (
+                    perception::perceive_targets,
+                    companion::assist_leader,
+                    ai::run_enemy_npc_ai,
+                    squad::coordinate_squads,
+                    patrol::walk_patrols,
+                    companion::follow_leader,
+                    navigation::invalidate_paths,
+                    navigation::follow_paths,
+                    squad::separate_npcs,
+                    ai::commit_npc_actions,
+                )
+                    .chain()
+                    .run_if(crate::dialogue::not_in_dialogue),
+            )
+            .add_systems(
+                Update,
+                (
+                    spawner::track_encounters,
+                    spawner::trigger_spawners,
+                    spawner::run_spawners,
+                )
+                    .chain()
+                    .run_if(crate::assets::loaded),
+            )
+            .add_systems(
+                FixedPostUpdate,
+                status::tick_status_conditions
+                    .before(crate::collision::physics::update_rigidbodies),
             )
             .add_systems(
                 PostUpdate,
@@ -87,3 +126,41 @@ pub enum NPCInfo {
     /// This is a character that is in the players party, and can draw enemy aggro.
     Team {},
 }
+
+impl NPCInfo {
+    /// Which side of a fight this NPC is on, if it fights at all.
+    pub fn faction(&self) -> Option<Faction> {
+        match self {
+            NPCInfo::Enemy { .. } => Some(Faction::Enemy),
+            NPCInfo::Team { .. } => Some(Faction::Team),
+            NPCInfo::None | NPCInfo::Silent => None,
+        }
+    }
+}
+
+/// Which side of a fight something is on.
+///
+/// The player is always on [`Faction::Team`].
+#[derive(Debug, Reflect, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Faction {
+    Team,
+    Enemy,
+}
+
+impl Faction {
+    /// The faction of an entity, from its [`NPCInfo`] and whether it's the player.
+    pub fn of(info: Option<&NPCInfo>, is_player: bool) -> Option<Self> {
+        if is_player {
+            return Some(Faction::Team);
+        }
+        info.and_then(NPCInfo::faction)
+    }
+
+    /// The faction of `entity`, see [`Faction::of`].
+    pub fn of_entity(world: &World, entity: Entity) -> Option<Self> {
+        Self::of(
+            world.get::<NPCInfo>(entity),
+            world.get::<crate::shaymin::Shaymin>(entity).is_some(),
+        )
+    }
+}
@@ -0,0 +1,76 @@
+use super::ai::NPCDesicion;
+use crate::ldtk::{TileQuery, TileSurfaces, tile_cell};
+use crate::mesh::TileChanged;
+use bevy::prelude::*;
+
+/// The path an NPC is following towards where it last decided to [`NPCDesicion::MoveTo`].
+///
+/// Kept around until the destination moves onto another tile, or the tiles change.
+#[derive(Debug, Component, Default, Clone)]
+pub struct NavPath {
+    /// The tile the path leads to.
+    goal: Option<IVec2>,
+    /// The tiles left to walk through, with the next one last.
+    waypoints: Vec<Vec3>,
+    /// Set when the tiles changed since the path was found.
+    stale: bool,
+}
+
+/// How close an NPC has to get to a waypoint before heading for the next one.
+const WAYPOINT_RADIUS: f32 = 0.25;
+
+/// Marks every [`NavPath`] to be found again once any tile changes,
+/// since it could have opened or blocked the way.
+pub(crate) fn invalidate_paths(
+    mut changed_tiles: EventReader<TileChanged>,
+    mut paths: Query<&mut NavPath>,
+) {
+    if changed_tiles.read().count() == 0 {
+        return;
+    }
+    for mut path in &mut paths {
+        path.stale = true;
+    }
+}
+
+/// Turns [`NPCDesicion::MoveTo`] into a [`NPCDesicion::Move`] towards the next tile along the NPC's [`NavPath`].
+///
+/// NPCs walk straight at their destination if there is no path there.
+pub(crate) fn follow_paths(
+    mut npcs: Query<(&mut NPCDesicion, &mut NavPath, &GlobalTransform)>,
+    tile_query: TileQuery,
+    tiles: TileSurfaces,
+) {
+    for (mut desicion, mut path, transform) in &mut npcs {
+        let NPCDesicion::MoveTo { destination } = *desicion else {
+            continue;
+        };
+        let position = transform.translation();
+
+        let goal = tile_cell(destination);
+        if path.stale || path.goal != Some(goal) {
+            let mut waypoints = tile_query
+                .find_path(&tiles, position, destination)
+                .unwrap_or_default();
+            waypoints.reverse();
+            *path = NavPath {
+                goal: Some(goal),
+                waypoints,
+                stale: false,
+            };
+        }
+
+        while path
+            .waypoints
+            .last()
+            .is_some_and(|next| next.xz().distance(position.xz()) <= WAYPOINT_RADIUS)
+        {
+            path.waypoints.pop();
+        }
+
+        let next = path.waypoints.last().copied().unwrap_or(destination);
+        *desicion = NPCDesicion::Move {
+            target: (next - position).with_y(0.0).normalize_or_zero(),
+        };
+    }
+}
@@ -0,0 +1,101 @@
+use super::ai::{NPCActions, NPCDesicion};
+use super::perception::Home;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// What an NPC does once it reaches the end of its [`Patrol`].
+#[derive(Debug, Reflect, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PatrolMode {
+    /// Heads back to the first point and goes around again.
+    #[default]
+    Loop,
+    /// Turns around and walks the route backwards.
+    PingPong,
+}
+
+/// A route an idle NPC walks, set by the `Patrol` points of an NPC placed in LDtk.
+///
+/// The NPC's [`Home`] follows it along the route,
+/// so it comes back to where it left off after giving up a chase.
+#[derive(Debug, Component, Reflect, Clone)]
+pub struct Patrol {
+    pub points: Vec<Vec3>,
+    pub mode: PatrolMode,
+    /// Seconds to wait at each point before walking to the next.
+    pub wait: f32,
+    next: usize,
+    backwards: bool,
+    waiting: f32,
+}
+
+impl Patrol {
+    pub fn new(points: Vec<Vec3>, mode: PatrolMode, wait: f32) -> Self {
+        Self {
+            points,
+            mode,
+            wait,
+            next: 0,
+            backwards: false,
+            waiting: 0.0,
+        }
+    }
+
+    /// Moves on to the point after the current one.
+    fn advance(&mut self) {
+        let last = self.points.len().saturating_sub(1);
+        match self.mode {
+            PatrolMode::Loop => self.next = (self.next + 1) % self.points.len().max(1),
+            PatrolMode::PingPong => {
+                if last == 0 {
+                    return;
+                }
+                if (self.backwards && self.next == 0) || (!self.backwards && self.next == last) {
+                    self.backwards = !self.backwards;
+                }
+                if self.backwards {
+                    self.next -= 1;
+                } else {
+                    self.next += 1;
+                }
+            }
+        }
+    }
+}
+
+/// How close an NPC has to get to a patrol point to have reached it.
+const POINT_RADIUS: f32 = 0.25;
+
+/// Walks idle NPCs along their [`Patrol`], waiting a moment at each point.
+///
+/// Only takes over if their behaviour didn't find anything else to do.
+pub(crate) fn walk_patrols(
+    mut npcs: Query<(
+        &NPCActions,
+        &mut NPCDesicion,
+        &mut Patrol,
+        &mut Home,
+        &GlobalTransform,
+    )>,
+    time: Res<Time>,
+) {
+    for (actions, mut desicion, mut patrol, mut home, transform) in &mut npcs {
+        if !matches!(actions, NPCActions::Idle { .. }) || !matches!(*desicion, NPCDesicion::Idle) {
+            continue;
+        }
+        let Some(point) = patrol.points.get(patrol.next).copied() else {
+            continue;
+        };
+        home.0 = point;
+
+        if transform.translation().xz().distance(point.xz()) > POINT_RADIUS {
+            *desicion = NPCDesicion::MoveTo { destination: point };
+            continue;
+        }
+
+        patrol.waiting += time.delta_secs();
+        if patrol.waiting >= patrol.wait {
+            patrol.waiting = 0.0;
+            patrol.advance();
+        }
+    }
+}
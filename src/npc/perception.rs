@@ -0,0 +1,125 @@
+use super::ai::NPCActions;
+use super::behaviour::line_of_sight;
+use super::stats::FacingDirection;
+use super::{Faction, NPCInfo};
+use crate::ldtk::{TileQuery, TileSurfaces};
+use crate::shaymin::Shaymin;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How an NPC notices things it's hostile to, and how far it chases them, as set in [`NPCData::perception`].
+///
+/// [`NPCData::perception`]: super::file::NPCData::perception
+#[derive(Debug, Component, Reflect, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Perception {
+    /// How far away the NPC can see, as long as nothing blocks the way.
+    pub sight_radius: f32,
+    /// How wide the NPC's view is around where it's facing, in degrees.
+    pub field_of_view: f32,
+    /// How close something can get before the NPC notices it from any direction, seen or not.
+    pub hearing_radius: f32,
+    /// How far from [`Home`] the NPC will chase something before giving up.
+    pub leash: f32,
+}
+
+impl Default for Perception {
+    fn default() -> Self {
+        Self {
+            sight_radius: 6.0,
+            field_of_view: 120.0,
+            hearing_radius: 2.0,
+            leash: 12.0,
+        }
+    }
+}
+
+impl Perception {
+    /// Returns `true` if something at `offset` from the NPC is close enough to be heard.
+    fn hears(&self, offset: Vec2) -> bool {
+        offset.length_squared() <= self.hearing_radius.powi(2)
+    }
+
+    /// Returns `true` if something at `offset` from the NPC is close enough and in view to be seen,
+    /// not counting whether the terrain blocks the way.
+    fn could_see(&self, facing: Dir2, offset: Vec2) -> bool {
+        offset.length_squared() <= self.sight_radius.powi(2)
+            && facing.angle_to(offset).to_degrees().abs() <= self.field_of_view / 2.0
+    }
+}
+
+/// Where an NPC returns to after giving up a chase.
+#[derive(Debug, Component, Reflect, Clone, Copy, Deref)]
+pub struct Home(pub Vec3);
+
+/// How close to [`Home`] an NPC has to be to be back.
+const HOME_RADIUS: f32 = 0.5;
+
+/// Switches idle NPCs to [`NPCActions::Offensive`] once they perceive something they're hostile to,
+/// and sends them [`NPCActions::Return`]ing home if the chase takes them past their leash
+/// or whatever they were after is gone.
+///
+/// Returning NPCs ignore everything until they're back.
+pub(crate) fn perceive_targets(
+    mut npcs: Query<(
+        Entity,
+        &NPCInfo,
+        &mut NPCActions,
+        &Perception,
+        &Home,
+        &FacingDirection,
+        &GlobalTransform,
+    )>,
+    others: Query<(Entity, Option<&NPCInfo>, Has<Shaymin>, &GlobalTransform)>,
+    tile_query: TileQuery,
+    tiles: TileSurfaces,
+) {
+    for (entity, info, mut actions, perception, home, facing, transform) in &mut npcs {
+        let Some(faction) = info.faction() else {
+            continue;
+        };
+        let position = transform.translation();
+        let hostile = |other_info, is_player| {
+            Faction::of(other_info, is_player).is_some_and(|other| other != faction)
+        };
+
+        match *actions {
+            NPCActions::Offensive { focus } => {
+                let still_hostile = others
+                    .get(focus)
+                    .is_ok_and(|(_, other_info, is_player, _)| hostile(other_info, is_player));
+                if !still_hostile || position.xz().distance(home.xz()) > perception.leash {
+                    *actions = NPCActions::Return { home: **home };
+                }
+            }
+            NPCActions::Return { home } => {
+                if position.xz().distance(home.xz()) <= HOME_RADIUS {
+                    *actions = NPCActions::default();
+                }
+            }
+            NPCActions::Defensive => (),
+            NPCActions::Idle { .. } => {
+                let noticed = others
+                    .iter()
+                    .filter(|(other, other_info, is_player, _)| {
+                        *other != entity && hostile(*other_info, *is_player)
+                    })
+                    .map(|(other, .., other_transform)| (other, other_transform.translation()))
+                    .filter(|(_, other_position)| {
+                        let offset = (other_position - position).xz();
+                        perception.hears(offset)
+                            || perception.could_see(**facing, offset)
+                                && line_of_sight(&tile_query, &tiles, position, *other_position)
+                    })
+                    .min_by(|(_, a), (_, b)| {
+                        a.distance_squared(position)
+                            .total_cmp(&b.distance_squared(position))
+                    });
+
+                if let Some((focus, _)) = noticed {
+                    *actions = NPCActions::Offensive { focus };
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use super::NPC;
+use super::commands::SpawnNPC;
+use crate::shaymin::Shaymin;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+/// What starts a [`Spawner`] spawning.
+#[derive(Debug, Reflect, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SpawnTrigger {
+    /// As soon as the map is loaded.
+    #[default]
+    OnStart,
+    /// Once the player walks into the spawner's area.
+    OnEnterArea,
+    /// Once every earlier wave of its [`Spawner::encounter`] is cleared.
+    AfterWave,
+}
+
+/// Spawns `count` NPCs over time, one every `interval` seconds,
+/// with no more than `max_alive` of them around at once.
+///
+/// Placed in LDtk as an entity tagged "Spawner".
+#[derive(Component, Reflect, Clone)]
+pub struct Spawner {
+    pub npc_id: NPC,
+    pub count: u32,
+    pub interval: f32,
+    pub max_alive: u32,
+    pub trigger: SpawnTrigger,
+    /// The area the player has to enter for [`SpawnTrigger::OnEnterArea`], on the ground plane.
+    pub area: Rect,
+    /// The encounter this spawner is a wave of, if any.
+    pub encounter: Option<String>,
+    /// Which wave of its encounter this is, starting from 0.
+    pub wave: u32,
+    spawned: u32,
+    timer: f32,
+    active: bool,
+}
+
+impl Spawner {
+    pub fn new(
+        npc_id: NPC,
+        count: u32,
+        interval: f32,
+        max_alive: u32,
+        trigger: SpawnTrigger,
+        area: Rect,
+    ) -> Self {
+        Self {
+            npc_id,
+            count,
+            interval,
+            max_alive,
+            trigger,
+            area,
+            encounter: None,
+            wave: 0,
+            spawned: 0,
+            timer: 0.0,
+            active: false,
+        }
+    }
+
+    pub fn with_encounter(mut self, encounter: String, wave: u32) -> Self {
+        self.encounter = Some(encounter);
+        self.wave = wave;
+        self
+    }
+
+    /// Returns `true` once it spawned everything, and all of it is gone.
+    fn finished(&self, alive: usize) -> bool {
+        self.spawned >= self.count && alive == 0
+    }
+}
+
+/// Points from an NPC to the [`Spawner`] it came from.
+#[derive(Debug, Component, Reflect)]
+#[relationship(relationship_target = SpawnedNPCs)]
+pub struct SpawnedBy(pub Entity);
+
+/// Every NPC a [`Spawner`] spawned that's still around.
+#[derive(Debug, Component, Reflect, Default)]
+#[relationship_target(relationship = SpawnedBy)]
+pub struct SpawnedNPCs(Vec<Entity>);
+
+/// Sent once every wave of an encounter has been cleared.
+#[derive(Debug, Event, Clone)]
+pub struct EncounterCleared {
+    pub encounter: String,
+}
+
+/// How far along each encounter is.
+#[derive(Debug, Resource, Default)]
+pub(crate) struct Encounters {
+    /// Encounters are cleared up to, but not including, this wave.
+    current_wave: HashMap<String, u32>,
+    cleared: Vec<String>,
+}
+
+/// Starts spawners once their [`SpawnTrigger`] is met.
+pub(crate) fn trigger_spawners(
+    mut spawners: Query<&mut Spawner>,
+    player: Query<&GlobalTransform, With<Shaymin>>,
+    encounters: Res<Encounters>,
+) {
+    let player = player
+        .single()
+        .ok()
+        .map(|transform| transform.translation().xz());
+    for mut spawner in &mut spawners {
+        if spawner.active {
+            continue;
+        }
+        spawner.active = match spawner.trigger {
+            SpawnTrigger::OnStart => true,
+            SpawnTrigger::OnEnterArea => player.is_some_and(|player| spawner.area.contains(player)),
+            SpawnTrigger::AfterWave => spawner.encounter.as_ref().is_none_or(|encounter| {
+                encounters
+                    .current_wave
+                    .get(encounter)
+                    .is_some_and(|wave| *wave >= spawner.wave)
+            }),
+        };
+    }
+}
+
+/// Spawns NPCs from active spawners through [`SpawnNPC`].
+pub(crate) fn run_spawners(
+    mut spawners: Query<(Entity, &mut Spawner, &GlobalTransform, Option<&SpawnedNPCs>)>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut spawner, transform, alive) in &mut spawners {
+        if !spawner.active || spawner.spawned >= spawner.count {
+            continue;
+        }
+        spawner.timer -= time.delta_secs();
+        let alive = alive.map_or(0, |alive| alive.len());
+        if spawner.timer > 0.0 || alive >= spawner.max_alive as usize {
+            continue;
+        }
+        spawner.timer = spawner.interval;
+        spawner.spawned += 1;
+        commands.queue(SpawnNPC {
+            npc_id: spawner.npc_id,
+            position: transform.translation(),
+            name: None,
+            squad: None,
+            patrol: None,
+            spawner: Some(entity),
+            dialogue: None,
+            party: false,
+        });
+    }
+}
+
+/// Moves encounters on to their next wave once every spawner in the current one is finished,
+/// and sends [`EncounterCleared`] once there are none left.
+pub(crate) fn track_encounters(
+    spawners: Query<(&Spawner, Option<&SpawnedNPCs>)>,
+    mut encounters: ResMut<Encounters>,
+    mut cleared: EventWriter<EncounterCleared>,
+) {
+    // the earliest wave with anything left to do is the one being fought
+    let mut current = HashMap::<String, Option<u32>>::new();
+    for (spawner, alive) in &spawners {
+        let Some(encounter) = &spawner.encounter else {
+            continue;
+        };
+        let wave = current.entry(encounter.clone()).or_default();
+        if !spawner.finished(alive.map_or(0, |alive| alive.len())) {
+            *wave = Some(wave.map_or(spawner.wave, |wave| wave.min(spawner.wave)));
+        }
+    }
+
+    for (encounter, wave) in current {
+        match wave {
+            Some(wave) => {
+                encounters.current_wave.insert(encounter, wave);
+            }
+            None if !encounters.cleared.contains(&encounter) => {
+                log::info!("Cleared encounter {}", encounter);
+                encounters.cleared.push(encounter.clone());
+                cleared.write(EncounterCleared { encounter });
+            }
+            None => (),
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use super::ai::{NPCActions, NPCDesicion};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+/// NPCs in the same squad that are after the same target work together,
+/// surrounding it and taking turns to attack instead of all piling in at once.
+///
+/// Set in [`NPCData::squad`], or by the `Squad` field of an NPC placed in LDtk.
+///
+/// [`NPCData::squad`]: super::file::NPCData::squad
+#[derive(Debug, Component, Reflect, Clone, PartialEq, Eq, Hash, Deref)]
+pub struct Squad(pub String);
+
+/// How an NPC is taking part in its [`Squad`]'s fight.
+#[derive(Debug, Component, Reflect, Clone, Default)]
+pub struct SquadMember {
+    /// Seconds left of this NPC's turn to attack, if it's their turn.
+    token: Option<f32>,
+    /// Seconds before this NPC can take another turn.
+    rest: f32,
+}
+
+/// How many NPCs in a squad can attack the same target at once.
+const ATTACK_TOKENS: usize = 1;
+
+/// Seconds an NPC gets to attack for before handing its turn to the next.
+const TURN_TIME: f32 = 2.0;
+
+/// Seconds an NPC waits after its turn before it can take another, if someone else is waiting.
+const REST_TIME: f32 = 1.0;
+
+/// How far from the target NPCs waiting for their turn circle around it.
+const SURROUND_RADIUS: f32 = 2.0;
+
+/// How close to its spot around the target a waiting NPC has to be to stop there.
+const SLOT_RADIUS: f32 = 0.25;
+
+/// How close NPCs can get to each other before they start steering apart.
+const SEPARATION_RADIUS: f32 = 1.0;
+
+/// How strongly NPCs steer away from each other, compared to where they're going.
+const SEPARATION_WEIGHT: f32 = 1.5;
+
+/// Hands out attack turns to squads fighting the same target,
+/// and sends everyone waiting for a turn to their own spot around the target.
+///
+/// Runs after the NPCs decided what to do, overriding what those waiting decided.
+pub(crate) fn coordinate_squads(
+    mut members: Query<(
+        Entity,
+        &Squad,
+        &NPCActions,
+        &mut SquadMember,
+        &mut NPCDesicion,
+        &GlobalTransform,
+    )>,
+    targets: Query<&GlobalTransform>,
+    time: Res<Time>,
+) {
+    let mut groups = HashMap::<(Squad, Entity), Vec<Entity>>::new();
+    for (entity, squad, actions, mut member, ..) in &mut members {
+        member.rest = (member.rest - time.delta_secs()).max(0.0);
+        match *actions {
+            NPCActions::Offensive { focus } => groups
+                .entry((squad.clone(), focus))
+                .or_default()
+                .push(entity),
+            // leaving the fight gives up the turn
+            _ => member.token = None,
+        }
+    }
+
+    for ((_, focus), group) in groups {
+        let Ok(target) = targets.get(focus).map(GlobalTransform::translation) else {
+            continue;
+        };
+
+        let mut holders = 0;
+        for entity in &group {
+            let (.., mut member, _, _) = members.get_mut(*entity).unwrap();
+            let Some(token) = member.token.as_mut() else {
+                continue;
+            };
+            *token -= time.delta_secs();
+            if *token <= 0.0 {
+                member.token = None;
+                member.rest = REST_TIME;
+            } else {
+                holders += 1;
+            }
+        }
+
+        // whoever is closest and rested goes next
+        let mut waiting: Vec<(Entity, Vec3)> = group
+            .iter()
+            .filter_map(|entity| {
+                let (.., member, _, transform) = members.get(*entity).unwrap();
+                member
+                    .token
+                    .is_none()
+                    .then_some((*entity, transform.translation()))
+            })
+            .collect();
+        waiting.sort_by(|(_, a), (_, b)| {
+            a.distance_squared(target)
+                .total_cmp(&b.distance_squared(target))
+        });
+        let mut next = waiting.iter().filter(|(entity, _)| {
+            let (.., member, _, _) = members.get(*entity).unwrap();
+            member.rest <= 0.0
+        });
+        let mut given = Vec::new();
+        while holders < ATTACK_TOKENS {
+            let Some((entity, _)) = next.next() else {
+                break;
+            };
+            given.push(*entity);
+            holders += 1;
+        }
+        // nobody rested is waiting, so let the ones that are keep going
+        if given.is_empty() && holders < ATTACK_TOKENS {
+            given.extend(waiting.first().map(|(entity, _)| *entity));
+        }
+        for entity in &given {
+            let (.., mut member, _, _) = members.get_mut(*entity).unwrap();
+            member.token = Some(TURN_TIME);
+        }
+        waiting.retain(|(entity, _)| !given.contains(entity));
+
+        // spread everyone else evenly around the target, in the order they already stand in
+        let angle_of = |position: Vec3| {
+            let offset = (position - target).xz();
+            offset.y.atan2(offset.x)
+        };
+        waiting.sort_by(|(_, a), (_, b)| angle_of(*a).total_cmp(&angle_of(*b)));
+        let Some(start) = waiting.first().map(|(_, position)| angle_of(*position)) else {
+            continue;
+        };
+        let step = TAU / waiting.len() as f32;
+        for (i, (entity, _)) in waiting.iter().enumerate() {
+            let (.., mut desicion, transform) = members.get_mut(*entity).unwrap();
+            if !matches!(
+                *desicion,
+                NPCDesicion::BasicAttack { .. } | NPCDesicion::MoveTo { .. }
+            ) {
+                continue;
+            }
+            let slot = target
+                + Vec2::from_angle(start + step * i as f32).extend(0.0).xzy() * SURROUND_RADIUS;
+            *desicion = if transform.translation().xz().distance(slot.xz()) <= SLOT_RADIUS {
+                NPCDesicion::Idle
+            } else {
+                NPCDesicion::MoveTo { destination: slot }
+            };
+        }
+    }
+}
+
+/// Steers moving NPCs away from each other so they don't bunch up on the same spot.
+pub(crate) fn separate_npcs(
+    mut npcs: Query<(Entity, &mut NPCDesicion, &GlobalTransform), With<NPCActions>>,
+    others: Query<(Entity, &GlobalTransform), With<NPCActions>>,
+) {
+    for (entity, mut desicion, transform) in &mut npcs {
+        let NPCDesicion::Move { target } = *desicion else {
+            continue;
+        };
+        let position = transform.translation().xz();
+        let push: Vec2 = others
+            .iter()
+            .filter(|(other, _)| *other != entity)
+            .filter_map(|(_, other_transform)| {
+                let away = position - other_transform.translation().xz();
+                let distance = away.length();
+                (distance < SEPARATION_RADIUS)
+                    .then(|| away.normalize_or(Vec2::X) * (1.0 - distance / SEPARATION_RADIUS))
+            })
+            .sum();
+        if push == Vec2::ZERO {
+            continue;
+        }
+        *desicion = NPCDesicion::Move {
+            target: (target + push.extend(0.0).xzy() * SEPARATION_WEIGHT).normalize_or_zero(),
+        };
+    }
+}
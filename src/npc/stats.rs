@@ -1,4 +1,4 @@
-use super::NPC;
+use super::{Faction, NPC};
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -25,6 +25,9 @@ impl Default for FacingDirection {
 #[require(NPC)]
 pub struct Health {
     pub hp: i64,
+    /// The most hp this entity can have, which it started with.
+    #[serde(skip)]
+    max: i64,
     /// can be set to true, but can never be set to false beyond initialization.
     /// thus code can always be certain that if they set it to be true,
     /// that they dont have to double check afterwards.
@@ -38,10 +41,19 @@ impl Health {
     pub fn new(hp: i64) -> Self {
         Self {
             hp,
+            max: hp,
             do_not_despawn_on_faint: false,
             currently_handling: false,
         }
     }
+
+    /// How much hp is left, as a fraction of the maximum.
+    pub fn ratio(&self) -> f32 {
+        if self.max <= 0 {
+            return 1.0;
+        }
+        self.hp as f32 / self.max as f32
+    }
 }
 
 impl std::ops::Deref for Health {
@@ -82,3 +94,33 @@ pub(crate) fn remove_dead(mut commands: Commands, query: Query<Entity, With<Dead
 #[derive(Debug, Component, Reflect, Serialize, Deserialize, Clone, Default, Deref)]
 #[require(NPC)]
 pub struct Damage(pub i64);
+
+/// How hard an attack pushes whatever it hits away, as a change in velocity.
+#[derive(Debug, Component, Reflect, Serialize, Deserialize, Clone, Copy, Default, Deref)]
+pub struct Knockback(pub f32);
+
+/// The side an attack was made by, which it won't hurt unless `friendly_fire` is set.
+#[derive(Debug, Component, Reflect, Clone, Copy)]
+pub struct AttackFaction {
+    pub faction: Faction,
+    pub friendly_fire: bool,
+}
+
+impl AttackFaction {
+    /// Returns `true` if the attack is allowed to hurt something on the `target` side.
+    pub fn can_hit(&self, target: Option<Faction>) -> bool {
+        self.friendly_fire || target != Some(self.faction)
+    }
+}
+
+/// Returns `true` if an attack made by `owner` is allowed to hurt `target`.
+///
+/// Attacks never hurt whoever made them, and only hurt the other side unless they have friendly fire.
+pub fn can_hurt(
+    attack: Option<&AttackFaction>,
+    owner: Entity,
+    target: Entity,
+    target_faction: Option<Faction>,
+) -> bool {
+    owner != target && attack.is_none_or(|attack| attack.can_hit(target_faction))
+}
@@ -0,0 +1,192 @@
+use super::animation::AnimationHandler;
+use super::stats::{AttackFaction, Health, can_hurt};
+use super::{Faction, NPCInfo};
+use crate::animation::AnimType;
+use crate::collision::{BasicCollider, CollisionEnterEvent, CollisionLayers};
+use crate::projectile::interfaces::ProjectileOwner;
+use crate::shaymin::Shaymin;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The different status conditions an entity can suffer from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum StatusKind {
+    /// Loses health every second.
+    Poison,
+    /// Loses a little health every second.
+    Burn,
+    /// Moves at half speed and cannot use moves.
+    Paralysis,
+    /// Falls over and cannot do anything until it wakes up.
+    Sleep,
+}
+
+// add default properties to variants here
+impl StatusKind {
+    /// How much health is lost every [`STATUS_TICK`] seconds.
+    pub fn tick_damage(self) -> Option<i64> {
+        match self {
+            StatusKind::Poison => Some(2),
+            StatusKind::Burn => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Multiplier applied to the speed an entity walks at.
+    pub fn speed_multiplier(self) -> f32 {
+        match self {
+            StatusKind::Paralysis => 0.5,
+            StatusKind::Sleep => 0.0,
+            _ => 1.0,
+        }
+    }
+
+    /// If `true`, the [`AnimationHandler`] is held in place until the condition ends.
+    pub fn locks_animation(self) -> bool {
+        matches!(self, StatusKind::Sleep)
+    }
+
+    /// If `true`, the NPC AI will not make any decisions.
+    pub fn prevents_action(self) -> bool {
+        matches!(self, StatusKind::Sleep)
+    }
+
+    /// If `true`, moves can't be used until the condition ends.
+    pub fn prevents_moves(self) -> bool {
+        matches!(self, StatusKind::Sleep | StatusKind::Paralysis)
+    }
+}
+
+/// Seconds between each bit of damage from [`StatusKind::tick_damage`].
+pub const STATUS_TICK: f32 = 1.0;
+
+/// Describes a status condition an attack can inflict, as set in move and projectile data.
+#[derive(Debug, Reflect, Serialize, Deserialize, Clone)]
+pub struct StatusInflict {
+    pub kind: StatusKind,
+    /// From `0.0` to `1.0`, the chance for a hit to inflict the condition.
+    pub chance: f32,
+    /// How long the condition lasts, in seconds.
+    pub duration: f32,
+}
+
+/// Inserted onto move and projectile entities that can inflict a status condition on hit.
+#[derive(Debug, Component, Reflect, Clone, Deref)]
+pub struct InflictsStatus(pub StatusInflict);
+
+/// A status condition that is currently affecting this entity.
+///
+/// Only one condition can be active at a time, new ones are ignored until it wears off.
+#[derive(Debug, Component, Reflect, Clone)]
+pub struct StatusCondition {
+    pub kind: StatusKind,
+    /// Seconds left until the condition wears off.
+    pub remaining: f32,
+    tick_timer: f32,
+}
+
+impl StatusCondition {
+    pub fn new(kind: StatusKind, duration: f32) -> Self {
+        Self {
+            kind,
+            remaining: duration,
+            tick_timer: 0.0,
+        }
+    }
+}
+
+/// If observed by an entity, hits from attacks with [`InflictsStatus`] may give it a [`StatusCondition`].
+///
+/// Like damage, statuses are only caught from attacks that are allowed to hurt the entity.
+pub fn catch_status(
+    trigger: Trigger<CollisionEnterEvent>,
+    mut commands: Commands,
+    attacks: Query<(
+        &InflictsStatus,
+        &BasicCollider,
+        Option<&AttackFaction>,
+        Option<&ProjectileOwner>,
+        Option<&ChildOf>,
+    )>,
+    targets: Query<(Option<&NPCInfo>, Has<Shaymin>)>,
+    current: Query<(), With<StatusCondition>>,
+) {
+    if current.contains(trigger.this) {
+        return;
+    }
+
+    let Ok((inflict, collider, faction, owner, child_of)) = attacks.get(trigger.other) else {
+        return;
+    };
+
+    let owner = owner.map_or_else(
+        || child_of.map_or(trigger.other, ChildOf::parent),
+        |owner| **owner,
+    );
+    let target_faction = targets
+        .get(trigger.this)
+        .ok()
+        .and_then(|(info, is_player)| Faction::of(info, is_player));
+    if !can_hurt(faction, owner, trigger.this, target_faction) {
+        return;
+    }
+
+    if !collider
+        .layers
+        .intersects(CollisionLayers::Projectile | CollisionLayers::Attack)
+    {
+        return;
+    }
+
+    if rand::random::<f32>() >= inflict.chance {
+        return;
+    }
+
+    log::info!("{} is now afflicted with {:?}", trigger.this, inflict.kind);
+    commands
+        .entity(trigger.this)
+        .insert(StatusCondition::new(inflict.kind, inflict.duration));
+}
+
+pub(crate) fn tick_status_conditions(
+    mut commands: Commands,
+    mut afflicted: Query<(
+        Entity,
+        &mut StatusCondition,
+        Option<&mut Health>,
+        Option<&mut AnimationHandler>,
+    )>,
+    time: Res<Time>,
+) {
+    for (entity, mut status, health, anim) in &mut afflicted {
+        let kind = status.kind;
+
+        status.remaining -= time.delta_secs();
+        if status.remaining <= 0.0 {
+            if kind.locks_animation()
+                && let Some(mut anim) = anim
+            {
+                anim.unlock();
+            }
+            commands.entity(entity).remove::<StatusCondition>();
+            continue;
+        }
+
+        if let Some(damage) = kind.tick_damage()
+            && let Some(mut health) = health
+        {
+            status.tick_timer += time.delta_secs();
+            while status.tick_timer >= STATUS_TICK {
+                status.tick_timer -= STATUS_TICK;
+                health.hp -= damage;
+            }
+        }
+
+        if kind.locks_animation()
+            && let Some(mut anim) = anim
+            && !anim.is_locked()
+        {
+            anim.lock(AnimType::Down);
+        }
+    }
+}
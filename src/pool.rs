@@ -0,0 +1,104 @@
+use std::hash::Hash;
+
+use bevy::ecs::entity_disabling::Disabled;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+/// Anything entities can be pooled by, such as the ID of a projectile.
+pub trait PoolKey: Copy + Eq + Hash + Send + Sync + 'static {}
+
+impl<K> PoolKey for K where K: Copy + Eq + Hash + Send + Sync + 'static {}
+
+/// Marks an entity as owned by the [`EntityPool`] for `K`,
+/// so it is kept around for reuse instead of being despawned.
+#[derive(Debug, Component, Clone, Copy)]
+pub struct Pooled<K: PoolKey>(pub K);
+
+/// Inactive entities waiting to be reused, sorted by what kind of entity they are.
+///
+/// Inactive entities are [`Disabled`], so they are skipped by every query until they are taken again.
+#[derive(Debug, Resource)]
+pub struct EntityPool<K: PoolKey> {
+    inactive: HashMap<K, Vec<Entity>>,
+}
+
+impl<K: PoolKey> Default for EntityPool<K> {
+    fn default() -> Self {
+        Self {
+            inactive: HashMap::default(),
+        }
+    }
+}
+
+impl<K: PoolKey> EntityPool<K> {
+    /// How many entities are waiting to be reused for `key`.
+    pub fn inactive(&self, key: K) -> usize {
+        self.inactive.get(&key).map_or(0, Vec::len)
+    }
+}
+
+/// Takes an inactive entity for `key` out of the pool and enables it again.
+///
+/// Returns [`None`] if the pool is empty, in which case a new entity should be spawned with [`Pooled`].
+/// The entity keeps whatever components it had when it was released.
+pub fn acquire<K: PoolKey>(world: &mut World, key: K) -> Option<Entity> {
+    loop {
+        let entity = world
+            .get_resource_or_init::<EntityPool<K>>()
+            .inactive
+            .get_mut(&key)?
+            .pop()?;
+        // the entity may have been despawned by something else while it was inactive
+        if let Ok(mut entity) = world.get_entity_mut(entity) {
+            entity.remove::<Disabled>();
+            return Some(entity.id());
+        }
+    }
+}
+
+/// Disables `entity` and returns it to the pool it belongs to.
+///
+/// Returns `false` if the entity doesn't exist or isn't [`Pooled`] by `K`, so it can be despawned instead.
+/// Releasing an entity that is already inactive does nothing.
+///
+/// The entity is handed out again by [`acquire`] with the same [`Entity`], generation included.
+/// Anything that holds on to it past this call, such as a component pointing at it or a command
+/// that runs later, will see whatever reuses it next. Copy what is needed out of it before releasing it,
+/// and don't keep pooled entities around as an owner or a source.
+pub fn release<K: PoolKey>(world: &mut World, entity: Entity) -> bool {
+    let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+        return false;
+    };
+    let Some(&Pooled(key)) = entity_mut.get::<Pooled<K>>() else {
+        return false;
+    };
+    if entity_mut.contains::<Disabled>() {
+        return true;
+    }
+
+    entity_mut.insert(Disabled);
+    world
+        .get_resource_or_init::<EntityPool<K>>()
+        .inactive
+        .entry(key)
+        .or_default()
+        .push(entity);
+    true
+}
+
+/// Fills the pool for `key` with inactive entities until it has at least `count` of them.
+///
+/// `spawn` creates each entity, which is marked as [`Pooled`] and released right after.
+pub fn prewarm<K: PoolKey>(
+    world: &mut World,
+    key: K,
+    count: usize,
+    mut spawn: impl FnMut(&mut World) -> Entity,
+) {
+    let missing = count.saturating_sub(world.get_resource_or_init::<EntityPool<K>>().inactive(key));
+    for _ in 0..missing {
+        let entity = spawn(world);
+        world.entity_mut(entity).insert(Pooled(key));
+        release::<K>(world, entity);
+    }
+}
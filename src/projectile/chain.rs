@@ -0,0 +1,124 @@
+use super::Projectile;
+use super::interfaces::{
+    DespawnProjectile, ProjectileCatalog, ProjectileData, ProjectileInterfaces, ProjectileMove,
+    ProjectileOwner,
+};
+use super::pattern::{EmissionPattern, SpawnProjectilePattern};
+use crate::moves::interfaces::MoveInfo;
+use crate::moves::lifecycle::MoveHit;
+use crate::npc::stats::FacingDirection;
+use bevy::ecs::entity_disabling::Disabled;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Projectiles fired by another projectile, as set in [`ProjectileData::on_hit`] and [`ProjectileData::on_expire`].
+///
+/// They are fired from wherever the first projectile is, in the direction it was flying,
+/// and are owned by whoever fired the first one.
+/// Area of effect zones are projectiles too, ones that don't move and last for a while.
+#[derive(Debug, Reflect, Serialize, Deserialize, Clone)]
+pub struct ProjectileSpawn {
+    pub projectile: Projectile,
+    #[serde(default)]
+    pub pattern: EmissionPattern,
+}
+
+/// Fires every [`ProjectileSpawn`] in `spawns`, with `projectile` as their source.
+fn fire_spawns(world: &mut World, projectile: Entity, spawns: Vec<ProjectileSpawn>) {
+    let Some(transform) = world.get::<GlobalTransform>(projectile) else {
+        return;
+    };
+    let position = transform.translation();
+    let direction = world
+        .get::<FacingDirection>(projectile)
+        .map_or(Dir2::X, |facing| **facing);
+
+    for spawn in spawns {
+        SpawnProjectilePattern {
+            source: Some(projectile),
+            position,
+            direction,
+            projectile_id: spawn.projectile,
+            pattern: spawn.pattern,
+        }
+        .apply(world);
+    }
+}
+
+fn get_projectile_data(world: &World, projectile: Entity) -> Option<ProjectileData> {
+    // pooled projectiles keep their ID while they wait to be reused
+    if world.get::<Disabled>(projectile).is_some() {
+        return None;
+    }
+    let projectile_id = world.get::<Projectile>(projectile)?;
+    let handle = world
+        .resource::<ProjectileCatalog>()
+        .data_files
+        .get(projectile_id)?;
+    world
+        .resource::<Assets<ProjectileData>>()
+        .get(handle)
+        .cloned()
+}
+
+/// Runs [`ProjectileInterface::on_hit`] and the [`ProjectileData::on_hit`] spawns
+/// of a projectile that just hit `target`,
+/// then reports the hit to the move that fired it if that move is still going.
+///
+/// [`ProjectileInterface::on_hit`]: super::ProjectileInterface::on_hit
+pub struct ProjectileHit {
+    pub projectile: Entity,
+    pub target: Entity,
+}
+
+impl Command for ProjectileHit {
+    fn apply(self, world: &mut World) {
+        let Some(data) = get_projectile_data(world, self.projectile) else {
+            return;
+        };
+        world.resource_scope(|world, mut interfaces: Mut<ProjectileInterfaces>| {
+            interfaces.interface_mut(data.variant).on_hit(
+                world,
+                self.projectile,
+                self.target,
+                &data,
+            );
+        });
+        fire_spawns(world, self.projectile, data.on_hit);
+
+        // hits that land after the move has ended have nothing left to report to
+        let Some(fired_by) = world.get::<ProjectileMove>(self.projectile).copied() else {
+            return;
+        };
+        let owner = world
+            .get::<ProjectileOwner>(self.projectile)
+            .map(|owner| **owner);
+        let same_move = world
+            .get::<MoveInfo>(fired_by.move_entity)
+            .is_some_and(|info| info.id == fired_by.move_id)
+            && world
+                .get::<ChildOf>(fired_by.move_entity)
+                .map(ChildOf::parent)
+                == owner;
+        if same_move {
+            MoveHit {
+                move_entity: fired_by.move_entity,
+                target: self.target,
+            }
+            .apply(world);
+        }
+    }
+}
+
+/// Removes a projectile that ran out, firing its [`ProjectileData::on_expire`] spawns first.
+pub struct ExpireProjectile(pub Entity);
+
+impl Command for ExpireProjectile {
+    fn apply(self, world: &mut World) {
+        // the projectile may have already been removed this frame
+        if let Some(data) = get_projectile_data(world, self.0) {
+            fire_spawns(world, self.0, data.on_expire);
+        }
+        DespawnProjectile(self.0).apply(world);
+    }
+}
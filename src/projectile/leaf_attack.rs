@@ -1,11 +1,9 @@
 use crate::assets::AnimationSpritesheet;
-use crate::moves::magical_leaf::MagicalLeaf;
 use crate::npc::animation::AnimationHandler;
-use crate::npc::stats::{Damage, FacingDirection};
+use crate::npc::stats::Damage;
 
 use super::interfaces::{ProjectileCatalog, ProjectileData};
 use super::{Projectile, ProjectileInterface};
-use crate::collision::{BasicCollider, ColliderShape};
 use bevy::prelude::*;
 
 #[derive(Component)]
@@ -13,7 +11,6 @@ pub struct LeafAttack;
 
 impl ProjectileInterface for LeafAttack {
     fn build(&mut self, app: &mut App) {
-        app.add_systems(FixedUpdate, process);
         // let world = app.world_mut();
         // let projectile_data = ProjectileData {
         //     variant: Projectile::LeafAttack,
@@ -47,36 +44,11 @@ impl ProjectileInterface for LeafAttack {
         source: Option<Entity>,
         projectile_data: &super::interfaces::ProjectileData,
     ) {
-        if let Some(source) = source {
-            let (magical_leaf, damage) = world
-                .query::<(&MagicalLeaf, &Damage)>()
-                .get(world, source)
-                .unwrap();
+        world.entity_mut(projectile_entity).insert(Self);
+        // any move can fire leaves, so take the damage of whichever move spawned them
+        if let Some(damage) = source.and_then(|source| world.get::<Damage>(source)) {
             let damage = damage.clone();
-            world.entity_mut(projectile_entity).insert((Self, damage));
+            world.entity_mut(projectile_entity).insert(damage);
         }
     }
 }
-
-pub fn process(
-    mut query: Query<(
-        Entity,
-        &LeafAttack,
-        &mut BasicCollider,
-        &mut Transform,
-        &FacingDirection,
-    )>,
-    time: Res<Time>,
-    mut commands: Commands,
-) {
-    for (entity, _leaf_attack, mut collider, mut transform, anim) in &mut query {
-        if let ColliderShape::Circle(radius) = &mut collider.shape {
-            *radius -= time.delta_secs();
-            if *radius <= 0.0 {
-                commands.entity(entity).despawn();
-            }
-        }
-
-        transform.translation += anim.xxy().with_y(0.0) * time.delta_secs() * 8.0;
-    }
-}
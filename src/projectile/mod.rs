@@ -1,62 +1,60 @@
 use bevy::prelude::*;
-use bevy_asset_loader::mapped::MapKey;
-use enum_iterator::Sequence;
 pub use interfaces::ProjectileInterface;
-use serde::{Deserialize, Serialize};
-
-#[derive(
-    Debug,
-    Component,
-    Default,
-    Reflect,
-    Sequence,
-    Serialize,
-    Deserialize,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Hash,
-)]
-pub enum Projectile {
-    #[default]
-    Void = 0,
-    LeafAttack,
+
+crate::registry::registry_id! {
+    /// The kind of a projectile.
+    ///
+    /// The ID of a projectile is the name of its data file in `projectile_data`,
+    /// and its [`ProjectileInterface`] is added with [`RegisterProjectileExt::register_projectile`].
+    ///
+    /// [`RegisterProjectileExt::register_projectile`]: interfaces::RegisterProjectileExt::register_projectile
+    Projectile
 }
 
+pub mod chain;
 mod leaf_attack;
+pub mod motion;
+pub mod pattern;
+pub mod rules;
+pub mod terrain;
 mod void;
 
-fn register_interface(input: Projectile) -> Box<dyn ProjectileInterface> {
-    match input {
-        Projectile::Void => Box::new(void::VoidedProjectile),
-        Projectile::LeafAttack => Box::new(leaf_attack::LeafAttack),
-    }
-}
-
-impl MapKey for Projectile {
-    fn from_asset_path(path: &bevy::asset::AssetPath) -> Self {
-        short_flight::from_asset_path(path)
-    }
+/// Registers the projectiles that need more than their data file.
+fn register_projectiles(app: &mut App) {
+    use interfaces::RegisterProjectileExt;
+    app.register_projectile("Void", void::VoidedProjectile)
+        .register_projectile("LeafAttack", leaf_attack::LeafAttack);
 }
 
 pub mod interfaces {
-    use super::{Projectile, register_interface};
+    use super::chain::ProjectileSpawn;
+    use super::motion::{ProjectileMotion, ProjectileMotionState};
+    use super::rules::{ProjectileHistory, ProjectileRules};
+    use super::terrain::TerrainImpact;
+    use super::void::VoidedProjectile;
+    use super::{Projectile, register_projectiles};
     use crate::assets::AnimationSpritesheet;
     use crate::billboard::Billboard;
+    use crate::collision::physics::Rigidbody;
     use crate::collision::{
-        BasicCollider, ColliderShape, CollisionLayers, DynamicCollision, ZHitbox,
+        BasicCollider, ColliderShape, CollisionLayers, DynamicCollision, TilemapCollision, ZHitbox,
     };
+    use crate::moves::Move;
+    use crate::moves::interfaces::MoveInfo;
+    use crate::npc::Faction;
     use crate::npc::animation::AnimationHandler;
-    use crate::npc::stats::{Damage, FacingDirection};
+    use crate::npc::stats::{AttackFaction, Damage, FacingDirection, Knockback};
+    use crate::npc::status::{InflictsStatus, StatusInflict};
     use crate::sprite3d::{Sprite3d, Sprite3dBuilder, Sprite3dBundle, Sprite3dParams};
+    use crate::typing::{AttackElement, ElementType};
     use bevy::asset::LoadState;
     use bevy::platform::collections::HashMap;
     use bevy::prelude::*;
     use bevy_asset_loader::asset_collection::AssetCollection;
     use serde::{Deserialize, Serialize};
+    use short_flight::pool::{self, Pooled};
 
+    /// Every projectile data file and image, keyed by the [`Projectile`] named after the file.
     #[derive(Resource, AssetCollection)]
     pub(crate) struct ProjectileCatalog {
         #[asset(path = "projectile_data", collection(typed, mapped))]
@@ -77,6 +75,36 @@ pub mod interfaces {
         pub(crate) collider: ColliderShape,
         #[serde(default)]
         pub(crate) damage: Damage,
+        /// The type of the projectile. If not set, the type of the source is used instead.
+        #[serde(default)]
+        pub(crate) element: Option<ElementType>,
+        /// A status condition the projectile can inflict on hit.
+        /// If not set, the condition of the source is used instead.
+        #[serde(default)]
+        pub(crate) status: Option<StatusInflict>,
+        /// How the projectile moves once fired.
+        #[serde(default)]
+        pub(crate) motion: ProjectileMotion,
+        /// When the projectile despawns.
+        #[serde(default)]
+        pub(crate) rules: ProjectileRules,
+        /// Projectiles fired whenever this one hits something, like a seed bursting into leaves.
+        #[serde(default)]
+        pub(crate) on_hit: Vec<ProjectileSpawn>,
+        /// Projectiles fired when this one runs out, like a rock leaving a cloud of dust behind.
+        #[serde(default)]
+        pub(crate) on_expire: Vec<ProjectileSpawn>,
+        /// What the projectile does when it flies into a wall.
+        #[serde(default)]
+        pub(crate) impact: TerrainImpact,
+        /// How hard the projectile pushes whatever it hits away.
+        /// If not set, the knockback of the source is used instead.
+        #[serde(default)]
+        pub(crate) knockback: Option<f32>,
+        /// Lets the projectile hurt its owner's own side, though never the owner.
+        /// Also set if the source has friendly fire.
+        #[serde(default)]
+        pub(crate) friendly_fire: bool,
         #[serde(skip)]
         pub(crate) assets: Option<ProjectileAssets>,
     }
@@ -93,6 +121,48 @@ pub mod interfaces {
         pub sprite3d_base: Sprite3d,
     }
 
+    impl ProjectileData {
+        /// Everything a projectile of this kind is spawned with, whoever fires it.
+        ///
+        /// Must not be called before the data is validated.
+        fn instance(&self, position: Vec3, direction: Dir2) -> impl Bundle {
+            let assets = self.assets.as_ref().unwrap();
+            (
+                self.variant,
+                FacingDirection(direction),
+                AnimationHandler::new(self.spritesheet.clone()),
+                BasicCollider::new(
+                    true,
+                    self.collider.clone(),
+                    CollisionLayers::Projectile | CollisionLayers::Attack,
+                    CollisionLayers::NPC,
+                ),
+                ZHitbox {
+                    y_tolerance: 0.5,
+                    neg_y_tolerance: 0.0,
+                },
+                Transform::from_translation(position)
+                    .with_rotation(Quat::from_rotation_x(f32::to_radians(-90.0))),
+                self.damage.clone(),
+                DynamicCollision::default(),
+                Sprite3dBundle {
+                    sprite_3d: assets.sprite3d_base.clone(),
+                    mesh: bevy::prelude::Mesh3d(assets.mesh.clone_weak()),
+                    material: bevy::prelude::MeshMaterial3d(assets.material.clone_weak()),
+                },
+                Billboard::default(),
+                TilemapCollision,
+                (
+                    self.motion.clone(),
+                    ProjectileMotionState::default(),
+                    self.rules.clone(),
+                    ProjectileHistory::default(),
+                    self.impact.clone(),
+                ),
+            )
+        }
+    }
+
     pub trait ProjectileInterface: Send + Sync {
         /// Initialize any useful schedules here.
         ///
@@ -108,29 +178,205 @@ pub mod interfaces {
         ) {
             // world.entity_mut(projectile_entity).insert(Self);
         }
+
+        /// Called when the projectile hits something with [`Health`], once per target.
+        ///
+        /// [`Health`]: crate::npc::stats::Health
+        fn on_hit(
+            &mut self,
+            world: &mut World,
+            projectile_entity: Entity,
+            target: Entity,
+            projectile_data: &ProjectileData,
+        ) {
+        }
     }
 
     pub struct ProjectilePlugin;
 
     impl Plugin for ProjectilePlugin {
         fn build(&self, app: &mut App) {
-            let mut interfaces = ProjectileInterfaces(
-                enum_iterator::all::<Projectile>()
-                    .into_iter()
-                    .map(|input| (input, register_interface(input)))
-                    .collect(),
-            );
-            for (_, registration) in interfaces.iter_mut() {
-                registration.build(app);
-            }
-            app.insert_resource(interfaces)
-                .add_systems(Update, validate_projectile_data);
+            app.init_resource::<ProjectileInterfaces>();
+            register_projectiles(app);
+            app.register_type::<ProjectileMotion>()
+                .register_type::<ProjectileMotionState>()
+                .register_type::<ProjectileRules>()
+                .register_type::<ProjectileHistory>()
+                .register_type::<TerrainImpact>()
+                .register_type::<super::pattern::EmissionPattern>()
+                .register_type::<ProjectileSpawn>()
+                .add_systems(Update, validate_projectile_data)
+                .add_systems(
+                    FixedUpdate,
+                    (
+                        super::motion::update_projectile_motion,
+                        super::rules::enforce_projectile_rules,
+                    )
+                        .chain(),
+                )
+                .add_systems(FixedUpdate, super::pattern::fire_bursts);
         }
     }
 
-    #[derive(Resource, Deref, DerefMut)]
+    /// The registry of every [`ProjectileInterface`], keyed by the ID of the projectile it runs.
+    ///
+    /// Projectiles that have a data file but were never registered
+    /// are spawned without any extra behaviour.
+    #[derive(Resource, Deref, DerefMut, Default)]
     pub struct ProjectileInterfaces(HashMap<Projectile, Box<dyn ProjectileInterface>>);
 
+    impl ProjectileInterfaces {
+        /// Returns the interface of the projectile, falling back to [`VoidedProjectile`].
+        pub fn interface_mut(
+            &mut self,
+            projectile_id: Projectile,
+        ) -> &mut Box<dyn ProjectileInterface> {
+            self.0
+                .entry(projectile_id)
+                .or_insert_with(|| Box::new(VoidedProjectile))
+        }
+    }
+
+    /// Lets any plugin add new projectiles at build time.
+    pub trait RegisterProjectileExt {
+        /// Registers `interface` to run the projectile named `name`,
+        /// and calls [`ProjectileInterface::build`].
+        fn register_projectile(
+            &mut self,
+            name: &str,
+            interface: impl ProjectileInterface + 'static,
+        ) -> &mut Self;
+    }
+
+    impl RegisterProjectileExt for App {
+        fn register_projectile(
+            &mut self,
+            name: &str,
+            mut interface: impl ProjectileInterface + 'static,
+        ) -> &mut Self {
+            interface.build(self);
+            let projectile_id = Projectile::new(name);
+            let mut interfaces = self
+                .world_mut()
+                .get_resource_or_init::<ProjectileInterfaces>();
+            if interfaces
+                .insert(projectile_id, Box::new(interface))
+                .is_some()
+            {
+                log::warn!("{} was registered more than once", projectile_id);
+            }
+            self
+        }
+    }
+
+    /// The entity that fired this projectile, looking past the move or projectile that spawned it.
+    #[derive(Debug, Component, Clone, Copy, Deref)]
+    pub struct ProjectileOwner(pub Entity);
+
+    /// Returns whoever is ultimately responsible for anything `source` fires.
+    ///
+    /// Moves are children of their user, and projectiles fired by other projectiles
+    /// keep the owner of the first one, so both are looked past.
+    /// Returns [`None`] if that's a projectile nobody fired, since it goes back to the pool
+    /// and its entity gets reused.
+    pub fn owner_of(world: &World, source: Entity) -> Option<Entity> {
+        if let Some(owner) = world.get::<ProjectileOwner>(source) {
+            return Some(**owner);
+        }
+        let owner = world
+            .get::<ChildOf>(source)
+            .map(ChildOf::parent)
+            .unwrap_or(source);
+        world
+            .get::<Pooled<Projectile>>(owner)
+            .is_none()
+            .then_some(owner)
+    }
+
+    /// The move that fired this projectile, so its hits can be reported back to the move.
+    ///
+    /// Moves are pooled as well, so the entity is only trusted while it is
+    /// still the same move, used by the same [`ProjectileOwner`].
+    #[derive(Debug, Component, Clone, Copy)]
+    pub struct ProjectileMove {
+        pub move_entity: Entity,
+        pub move_id: Move,
+    }
+
+    /// Returns the move behind anything `source` fires, looking past projectiles fired by projectiles.
+    pub fn move_of(world: &World, source: Entity) -> Option<ProjectileMove> {
+        if let Some(fired_by) = world.get::<ProjectileMove>(source) {
+            return Some(*fired_by);
+        }
+        world.get::<MoveInfo>(source).map(|info| ProjectileMove {
+            move_entity: source,
+            move_id: info.id,
+        })
+    }
+
+    /// How many inactive entities are kept ready for each kind of projectile once its data is loaded.
+    const PREWARMED_PROJECTILES: usize = 16;
+
+    /// Components [`SpawnProjectile`] only adds to some projectiles,
+    /// which are taken off again before the entity goes back to the pool.
+    type ProjectileExtras = (
+        ProjectileOwner,
+        ProjectileMove,
+        Rigidbody,
+        AttackElement,
+        InflictsStatus,
+        Knockback,
+        AttackFaction,
+    );
+
+    /// Fills the pool of a projectile with inactive entities, so firing it doesn't need to spawn any.
+    pub struct PrewarmProjectiles(pub Projectile);
+
+    impl Command for PrewarmProjectiles {
+        fn apply(self, world: &mut World) {
+            let Some(data) = world
+                .resource::<ProjectileCatalog>()
+                .data_files
+                .get(&self.0)
+                .and_then(|handle| world.resource::<Assets<ProjectileData>>().get(handle))
+                .filter(|data| data.assets.is_some())
+                .cloned()
+            else {
+                log::error!("Could not prewarm {}, its data is not ready", self.0);
+                return;
+            };
+            pool::prewarm(world, self.0, PREWARMED_PROJECTILES, |world| {
+                spawn_pooled(world, self.0, data.instance(Vec3::ZERO, Dir2::X))
+            });
+        }
+    }
+
+    /// Spawns a new projectile entity for the pool, along with the observers every projectile keeps.
+    fn spawn_pooled(world: &mut World, projectile_id: Projectile, bundle: impl Bundle) -> Entity {
+        world
+            .spawn((Pooled(projectile_id), bundle))
+            .observe(super::terrain::hit_terrain)
+            .observe(super::terrain::leave_terrain)
+            .id()
+    }
+
+    /// Removes a projectile, returning it to the pool so the entity can be reused.
+    ///
+    /// Use this instead of despawning projectiles directly.
+    pub struct DespawnProjectile(pub Entity);
+
+    impl Command for DespawnProjectile {
+        fn apply(self, world: &mut World) {
+            if !pool::release::<Projectile>(world, self.0) {
+                if let Ok(entity) = world.get_entity_mut(self.0) {
+                    entity.despawn();
+                }
+                return;
+            }
+            world.entity_mut(self.0).remove::<ProjectileExtras>();
+        }
+    }
+
     pub struct SpawnProjectile {
         pub source: Option<Entity>,
         pub position: Vec3,
@@ -157,9 +403,10 @@ pub mod interfaces {
                 .expect("The projectile catalog is missing an image variant.")
                 .clone_weak();
 
-            let handle = catalog.data_files.get(&self.projectile_id).expect(
-                "The projectile catalog MUST exhaustively contain all projectile variants.",
-            );
+            let handle = catalog
+                .data_files
+                .get(&self.projectile_id)
+                .expect("The projectile catalog is missing a data file for this projectile.");
             let data = data_assets.get(handle).expect(
                 "SpawnProjectile should not be called before all projectile data assets are loaded.",
             ).clone();
@@ -186,33 +433,77 @@ pub mod interfaces {
                 )
             }
 
-            let id = world
-                .spawn((
-                    self.projectile_id,
-                    FacingDirection(self.direction),
-                    AnimationHandler::new(data.spritesheet.clone()),
-                    BasicCollider::new(
-                        true,
-                        data.collider.clone(),
-                        CollisionLayers::Projectile & CollisionLayers::Attack,
-                        CollisionLayers::NPC,
-                    ),
-                    ZHitbox {
-                        y_tolerance: 0.5,
-                        neg_y_tolerance: 0.0,
-                    },
-                    Transform::from_translation(self.position)
-                        .with_rotation(Quat::from_rotation_x(f32::to_radians(-90.0))),
-                    data.damage.clone(),
-                    DynamicCollision::default(),
-                    Sprite3dBundle {
-                        sprite_3d: assets.sprite3d_base.clone(),
-                        mesh: bevy::prelude::Mesh3d(assets.mesh.clone_weak()),
-                        material: bevy::prelude::MeshMaterial3d(assets.material.clone_weak()),
-                    },
-                    Billboard::default(),
-                ))
-                .id();
+            let bundle = data.instance(self.position, self.direction);
+            let id = match pool::acquire(world, self.projectile_id) {
+                Some(id) => {
+                    world.entity_mut(id).insert(bundle);
+                    id
+                }
+                None => spawn_pooled(world, self.projectile_id, bundle),
+            };
+
+            let owner = self.source.and_then(|source| owner_of(world, source));
+            if let Some(owner) = owner {
+                world.entity_mut(id).insert(ProjectileOwner(owner));
+            }
+
+            if let Some(fired_by) = self.source.and_then(|source| move_of(world, source)) {
+                world.entity_mut(id).insert(fired_by);
+            }
+
+            if let Some(velocity) = data.motion.launch_velocity(self.direction) {
+                world.entity_mut(id).insert(Rigidbody {
+                    velocity,
+                    ..Default::default()
+                });
+            }
+
+            let attack_element = match (data.element, owner) {
+                (Some(element), Some(user)) => Some(AttackElement::for_user(world, element, user)),
+                (Some(element), None) => Some(AttackElement {
+                    element,
+                    same_type: false,
+                }),
+                (None, _) => self
+                    .source
+                    .and_then(|source| world.get::<AttackElement>(source).cloned()),
+            };
+            if let Some(attack_element) = attack_element {
+                world.entity_mut(id).insert(attack_element);
+            }
+
+            let status = match (data.status.clone(), self.source) {
+                (Some(status), _) => Some(InflictsStatus(status)),
+                (None, Some(source)) => world.get::<InflictsStatus>(source).cloned(),
+                (None, None) => None,
+            };
+            if let Some(status) = status {
+                world.entity_mut(id).insert(status);
+            }
+
+            let knockback = match (data.knockback, self.source) {
+                (Some(knockback), _) => Some(Knockback(knockback)),
+                (None, Some(source)) => world.get::<Knockback>(source).copied(),
+                (None, None) => None,
+            };
+            if let Some(knockback) = knockback {
+                world.entity_mut(id).insert(knockback);
+            }
+
+            // chained projectiles keep the side of the first one, even if their owner is gone
+            let source_faction = self
+                .source
+                .and_then(|source| world.get::<AttackFaction>(source).copied());
+            let faction = source_faction
+                .map(|attack| attack.faction)
+                .or_else(|| owner.and_then(|owner| Faction::of_entity(world, owner)));
+            if let Some(faction) = faction {
+                world.entity_mut(id).insert(AttackFaction {
+                    faction,
+                    friendly_fire: data.friendly_fire
+                        || source_faction.is_some_and(|attack| attack.friendly_fire),
+                });
+            }
 
             log::info!(
                 "Spawning projectile: Source [{}] ID [{:?}] Entity [{}]",
@@ -224,10 +515,7 @@ pub mod interfaces {
             world.resource_scope(
                 |world, mut projectile_interfaces: Mut<ProjectileInterfaces>| {
                     projectile_interfaces
-                        .get_mut(&self.projectile_id)
-                        .expect(
-                            "Expected ProjectileInterfaces to exhaustively contain every variant",
-                        )
+                        .interface_mut(self.projectile_id)
                         .on_spawn(world, id, self.source, &data);
                 },
             );
@@ -240,6 +528,7 @@ pub mod interfaces {
         catalog: Option<Res<ProjectileCatalog>>,
         asset_server: Res<AssetServer>,
         mut sprite3d_params: Sprite3dParams,
+        mut commands: Commands,
     ) {
         let Some(catalog) = catalog else { return };
         for event in asset_events.read() {
@@ -283,6 +572,8 @@ pub mod interfaces {
                         atlas: data.spritesheet.atlas.as_ref().unwrap().clone(),
                     });
 
+                    commands.queue(PrewarmProjectiles(data.variant));
+
                     log::info!("Validated")
                 }
                 _ => (),
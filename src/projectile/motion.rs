@@ -0,0 +1,215 @@
+use super::chain::ExpireProjectile;
+use super::interfaces::ProjectileOwner;
+use crate::collision::BasicCollider;
+use crate::collision::physics::Rigidbody;
+use crate::npc::stats::{AttackFaction, FacingDirection, Health, can_hurt};
+use crate::npc::{Faction, NPCInfo};
+use crate::shaymin::Shaymin;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How a projectile moves, as set in [`ProjectileData::motion`].
+///
+/// Every model is run by [`update_projectile_motion`], so new projectiles only need a data file.
+///
+/// [`ProjectileData::motion`]: super::interfaces::ProjectileData::motion
+#[derive(Debug, Component, Reflect, Serialize, Deserialize, Clone)]
+pub enum ProjectileMotion {
+    /// Flies straight ahead, changing speed by `acceleration` every second.
+    Linear {
+        speed: f32,
+        #[serde(default)]
+        acceleration: f32,
+    },
+    /// Turns toward the nearest valid target by up to `turn_rate` degrees a second.
+    ///
+    /// Only targets within `range` are considered, if it is set.
+    Homing {
+        speed: f32,
+        turn_rate: f32,
+        #[serde(default)]
+        range: Option<f32>,
+    },
+    /// Launched upward with `lift`, and brought back down by the [`Rigidbody`] gravity.
+    ///
+    /// [`Rigidbody`]: crate::collision::physics::Rigidbody
+    Ballistic { speed: f32, lift: f32 },
+    /// Flies out for `distance`, then returns to whoever fired it and expires once it's back,
+    /// or as soon as they're gone.
+    Boomerang { speed: f32, distance: f32 },
+    /// Flies straight ahead while swaying `amplitude` to each side, `frequency` times a second.
+    Sine {
+        speed: f32,
+        amplitude: f32,
+        frequency: f32,
+    },
+}
+
+impl Default for ProjectileMotion {
+    fn default() -> Self {
+        Self::Linear {
+            speed: 8.0,
+            acceleration: 0.0,
+        }
+    }
+}
+
+impl ProjectileMotion {
+    /// The velocity the projectile is launched with, for models that are moved by a [`Rigidbody`].
+    ///
+    /// [`Rigidbody`]: crate::collision::physics::Rigidbody
+    pub fn launch_velocity(&self, direction: Dir2) -> Option<Vec3> {
+        match *self {
+            ProjectileMotion::Ballistic { speed, lift } => {
+                Some((*direction * speed).extend(lift).xzy())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Per-entity progress of a [`ProjectileMotion`].
+#[derive(Debug, Component, Reflect, Default)]
+pub struct ProjectileMotionState {
+    /// Seconds since the projectile was spawned.
+    pub elapsed: f32,
+    /// Distance flown so far.
+    pub traveled: f32,
+    /// Set once a [`ProjectileMotion::Boomerang`] turns back.
+    returning: bool,
+}
+
+/// How close a returning boomerang needs to get to its owner before it is caught.
+const CATCH_DISTANCE: f32 = 0.5;
+
+pub(crate) fn update_projectile_motion(
+    mut commands: Commands,
+    mut projectiles: Query<(
+        Entity,
+        &ProjectileMotion,
+        &mut ProjectileMotionState,
+        &mut Transform,
+        &mut FacingDirection,
+        &BasicCollider,
+        Option<&ProjectileOwner>,
+        Option<&AttackFaction>,
+        Option<&Rigidbody>,
+    )>,
+    targets: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &BasicCollider,
+            Option<&NPCInfo>,
+            Has<Shaymin>,
+        ),
+        With<Health>,
+    >,
+    owners: Query<&GlobalTransform>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+    for (
+        entity,
+        motion,
+        mut state,
+        mut transform,
+        mut facing,
+        collider,
+        owner,
+        attack,
+        rigidbody,
+    ) in &mut projectiles
+    {
+        let owner = owner.map(|owner| **owner);
+        let position = transform.translation;
+        let forward = **facing;
+
+        let movement = match *motion {
+            ProjectileMotion::Linear {
+                speed,
+                acceleration,
+            } => *forward * (speed + acceleration * state.elapsed).max(0.0) * delta,
+            ProjectileMotion::Homing {
+                speed,
+                turn_rate,
+                range,
+            } => {
+                let target = targets
+                    .iter()
+                    // only home in on what it's allowed to hurt
+                    .filter(|(target, _, target_collider, info, is_player)| {
+                        can_hurt(
+                            attack,
+                            owner.unwrap_or(entity),
+                            *target,
+                            Faction::of(*info, *is_player),
+                        ) && collider
+                            .can_interact
+                            .intersects(target_collider.layers.clone())
+                    })
+                    .map(|(_, target_transform, ..)| target_transform.translation().xz())
+                    .filter(|target| {
+                        range.is_none_or(|range| {
+                            target.distance_squared(position.xz()) <= range.powi(2)
+                        })
+                    })
+                    .min_by(|a, b| {
+                        a.distance_squared(position.xz())
+                            .total_cmp(&b.distance_squared(position.xz()))
+                    });
+
+                if let Some(target) = target
+                    && let Ok(desired) = Dir2::new(target - position.xz())
+                {
+                    let max_turn = turn_rate.to_radians() * delta;
+                    let turn = forward.angle_to(*desired).clamp(-max_turn, max_turn);
+                    facing.set(Rot2::radians(turn) * forward);
+                }
+                ***facing * speed * delta
+            }
+            // moved by its rigidbody instead
+            ProjectileMotion::Ballistic { .. } => Vec2::ZERO,
+            ProjectileMotion::Boomerang { speed, distance } => {
+                if state.traveled >= distance {
+                    state.returning = true;
+                }
+
+                if state.returning {
+                    let Some(owner_position) = owner
+                        .and_then(|owner| owners.get(owner).ok())
+                        .map(|owner| owner.translation().xz())
+                    else {
+                        commands.queue(ExpireProjectile(entity));
+                        continue;
+                    };
+                    let to_owner = owner_position - position.xz();
+                    if to_owner.length() <= CATCH_DISTANCE {
+                        commands.queue(ExpireProjectile(entity));
+                        continue;
+                    }
+                    if let Ok(direction) = Dir2::new(to_owner) {
+                        facing.set(direction);
+                    }
+                }
+                ***facing * speed * delta
+            }
+            ProjectileMotion::Sine {
+                speed,
+                amplitude,
+                frequency,
+            } => {
+                let angular = std::f32::consts::TAU * frequency;
+                let sway = amplitude
+                    * ((angular * (state.elapsed + delta)).sin() - (angular * state.elapsed).sin());
+                *forward * speed * delta + forward.perp() * sway
+            }
+        };
+
+        // rigidbodies move themselves, so count how far they went instead
+        let flown = rigidbody.map_or(movement, |rigidbody| rigidbody.velocity.xz() * delta);
+        state.elapsed += delta;
+        state.traveled += flown.length();
+        transform.translation += movement.extend(0.0).xzy();
+    }
+}
@@ -0,0 +1,274 @@
+use super::Projectile;
+use super::interfaces::{ProjectileOwner, SpawnProjectile, move_of, owner_of};
+use crate::npc::stats::{AttackFaction, Damage, Health, Knockback};
+use crate::npc::status::InflictsStatus;
+use crate::npc::{Faction, NPCInfo};
+use crate::shaymin::Shaymin;
+use crate::typing::AttackElement;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How many projectiles are fired at once, and in which directions.
+#[derive(Debug, Reflect, Serialize, Deserialize, Clone, Default)]
+pub enum EmissionPattern {
+    /// A single projectile straight ahead.
+    #[default]
+    Single,
+    /// `count` projectiles fanned out evenly across `angle` degrees, centered on the direction.
+    Spread { count: u32, angle: f32 },
+    /// `count` projectiles straight ahead, one every `interval` seconds.
+    Burst { count: u32, interval: f32 },
+    /// `count` projectiles spread evenly all the way around.
+    Radial { count: u32 },
+    /// `count` projectiles side by side, `spacing` apart, all aimed at the nearest target.
+    ///
+    /// Only targets within `range` are aimed at, if it is set,
+    /// and the projectiles fly straight ahead if there are none.
+    Volley {
+        count: u32,
+        spacing: f32,
+        #[serde(default)]
+        range: Option<f32>,
+    },
+}
+
+/// Degrees between the projectiles of a [`EmissionPattern::Single`] once it has extra ones,
+/// or of a spread that had no angle between its projectiles yet.
+const EXTRA_SPREAD_STEP: f32 = 15.0;
+
+impl EmissionPattern {
+    /// Adds `extra` projectiles to the pattern.
+    ///
+    /// Spreads keep the same angle between every projectile, so they fan out wider.
+    /// A single projectile becomes a spread, [`EXTRA_SPREAD_STEP`] degrees apart.
+    pub fn with_extra(&self, extra: u32) -> Self {
+        match *self {
+            EmissionPattern::Single if extra > 0 => EmissionPattern::Spread {
+                count: extra + 1,
+                angle: EXTRA_SPREAD_STEP * extra as f32,
+            },
+            EmissionPattern::Single => EmissionPattern::Single,
+            EmissionPattern::Spread { count, angle } => {
+                let step = match angle / count.saturating_sub(1).max(1) as f32 {
+                    step if count > 1 && step > 0.0 => step,
+                    _ => EXTRA_SPREAD_STEP,
+                };
+                EmissionPattern::Spread {
+                    count: count + extra,
+                    angle: angle + step * extra as f32,
+                }
+            }
+            EmissionPattern::Burst { count, interval } => EmissionPattern::Burst {
+                count: count + extra,
+                interval,
+            },
+            EmissionPattern::Radial { count } => EmissionPattern::Radial {
+                count: count + extra,
+            },
+            EmissionPattern::Volley {
+                count,
+                spacing,
+                range,
+            } => EmissionPattern::Volley {
+                count: count + extra,
+                spacing,
+                range,
+            },
+        }
+    }
+}
+
+/// Fires a batch of projectiles in an [`EmissionPattern`], each one through [`SpawnProjectile`].
+pub struct SpawnProjectilePattern {
+    pub source: Option<Entity>,
+    pub position: Vec3,
+    pub direction: Dir2,
+    pub projectile_id: Projectile,
+    pub pattern: EmissionPattern,
+}
+
+impl Command for SpawnProjectilePattern {
+    fn apply(self, world: &mut World) {
+        let shot = |position: Vec3, direction: Dir2| SpawnProjectile {
+            source: self.source,
+            position,
+            direction,
+            projectile_id: self.projectile_id,
+        };
+
+        match self.pattern {
+            EmissionPattern::Single => shot(self.position, self.direction).apply(world),
+            EmissionPattern::Spread { count, angle } => {
+                let step = angle / count.saturating_sub(1).max(1) as f32;
+                for i in 0..count {
+                    let offset = (i as f32 - count.saturating_sub(1) as f32 / 2.0) * step;
+                    shot(self.position, Rot2::degrees(offset) * self.direction).apply(world);
+                }
+            }
+            EmissionPattern::Burst { count, interval } => {
+                shot(self.position, self.direction).apply(world);
+                if count > 1 {
+                    let burst = world
+                        .spawn(ProjectileBurst {
+                            position: self.position,
+                            direction: self.direction,
+                            projectile_id: self.projectile_id,
+                            remaining: count - 1,
+                            interval,
+                            timer: interval,
+                        })
+                        .id();
+                    if let Some(source) = self.source {
+                        copy_source(world, source, burst);
+                    }
+                }
+            }
+            EmissionPattern::Radial { count } => {
+                let step = 360.0 / count.max(1) as f32;
+                for i in 0..count {
+                    shot(
+                        self.position,
+                        Rot2::degrees(step * i as f32) * self.direction,
+                    )
+                    .apply(world);
+                }
+            }
+            EmissionPattern::Volley {
+                count,
+                spacing,
+                range,
+            } => {
+                let target = nearest_target(world, self.source, self.position, range);
+                let aim = target
+                    .and_then(|target| Dir2::new((target - self.position).xz()).ok())
+                    .unwrap_or(self.direction);
+                let side = aim.perp().extend(0.0).xzy();
+                for i in 0..count {
+                    let offset = (i as f32 - (count.saturating_sub(1)) as f32 / 2.0) * spacing;
+                    let position = self.position + side * offset;
+                    // converge on the target instead of flying past either side of it
+                    let direction = target
+                        .and_then(|target| Dir2::new((target - position).xz()).ok())
+                        .unwrap_or(aim);
+                    shot(position, direction).apply(world);
+                }
+            }
+        }
+    }
+}
+
+/// The position of the closest entity with [`Health`] the projectiles are allowed to hurt.
+fn nearest_target(
+    world: &mut World,
+    source: Option<Entity>,
+    position: Vec3,
+    range: Option<f32>,
+) -> Option<Vec3> {
+    let owner = source.and_then(|source| owner_of(world, source));
+    // projectiles take the side of their source, or their owner if it has none
+    let attack = source
+        .and_then(|source| world.get::<AttackFaction>(source).copied())
+        .or_else(|| {
+            let faction = Faction::of_entity(world, owner?)?;
+            Some(AttackFaction {
+                faction,
+                friendly_fire: false,
+            })
+        });
+    world
+        .query_filtered::<(Entity, &GlobalTransform, Option<&NPCInfo>, Has<Shaymin>), With<Health>>(
+        )
+        .iter(world)
+        .filter(|(entity, _, info, is_player)| {
+            Some(*entity) != owner
+                && attack.is_none_or(|attack| attack.can_hit(Faction::of(*info, *is_player)))
+        })
+        .map(|(_, transform, ..)| transform.translation())
+        .filter(|target| {
+            range.is_none_or(|range| target.xz().distance_squared(position.xz()) <= range.powi(2))
+        })
+        .min_by(|a, b| {
+            a.xz()
+                .distance_squared(position.xz())
+                .total_cmp(&b.xz().distance_squared(position.xz()))
+        })
+}
+
+/// Copies everything [`SpawnProjectile`] reads from `source` onto `burst`, along with its owner and move.
+///
+/// Moves and projectiles are pooled, so by the time the rest of a burst is fired
+/// the entity that started it may have been reused for something else.
+fn copy_source(world: &mut World, source: Entity, burst: Entity) {
+    let owner = owner_of(world, source);
+    let fired_by = move_of(world, source);
+    let source = world.entity(source);
+    let name = source.get::<Name>().cloned();
+    let damage = source.get::<Damage>().cloned();
+    let element = source.get::<AttackElement>().cloned();
+    let status = source.get::<InflictsStatus>().cloned();
+    let knockback = source.get::<Knockback>().copied();
+    let faction = source.get::<AttackFaction>().copied();
+
+    let mut burst = world.entity_mut(burst);
+    if let Some(owner) = owner {
+        burst.insert(ProjectileOwner(owner));
+    }
+    if let Some(fired_by) = fired_by {
+        burst.insert(fired_by);
+    }
+    if let Some(name) = name {
+        burst.insert(name);
+    }
+    if let Some(damage) = damage {
+        burst.insert(damage);
+    }
+    if let Some(element) = element {
+        burst.insert(element);
+    }
+    if let Some(status) = status {
+        burst.insert(status);
+    }
+    if let Some(knockback) = knockback {
+        burst.insert(knockback);
+    }
+    if let Some(faction) = faction {
+        burst.insert(faction);
+    }
+}
+
+/// The rest of an [`EmissionPattern::Burst`], fired over time from where it started.
+///
+/// The burst entity stands in as the source of every later shot,
+/// with a copy of what the first shot's source had.
+#[derive(Debug, Component)]
+pub struct ProjectileBurst {
+    position: Vec3,
+    direction: Dir2,
+    projectile_id: Projectile,
+    remaining: u32,
+    interval: f32,
+    timer: f32,
+}
+
+pub(crate) fn fire_bursts(
+    mut bursts: Query<(Entity, &mut ProjectileBurst)>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut burst) in &mut bursts {
+        burst.timer -= time.delta_secs();
+        while burst.timer <= 0.0 && burst.remaining > 0 {
+            burst.timer += burst.interval;
+            burst.remaining -= 1;
+            commands.queue(SpawnProjectile {
+                source: Some(entity),
+                position: burst.position,
+                direction: burst.direction,
+                projectile_id: burst.projectile_id,
+            });
+        }
+        if burst.remaining == 0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use super::chain::ExpireProjectile;
+use super::motion::ProjectileMotionState;
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// When a projectile despawns, as set in [`ProjectileData::rules`].
+///
+/// Every rule is enforced by [`enforce_projectile_rules`], and a projectile expires
+/// as soon as any one of them is broken.
+///
+/// [`ProjectileData::rules`]: super::interfaces::ProjectileData::rules
+#[derive(Debug, Component, Reflect, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ProjectileRules {
+    /// Seconds the projectile lasts for.
+    pub lifetime: Option<f32>,
+    /// How far the projectile can fly.
+    pub range: Option<f32>,
+    /// How many targets the projectile passes through after the first one, before despawning.
+    /// `Some(0)` stops at the first hit, and if not set it can hit any number.
+    pub pierce: Option<u32>,
+    /// How many times the projectile bounces off walls before despawning.
+    pub bounces: u32,
+    /// Despawns the projectile on its first hit, no matter how much pierce it has.
+    pub despawn_on_hit: bool,
+}
+
+impl ProjectileRules {
+    /// Returns `true` once the projectile should no longer exist.
+    pub fn expired(&self, state: &ProjectileMotionState, history: &ProjectileHistory) -> bool {
+        let hits = history.hits.len() as u32;
+        self.lifetime
+            .is_some_and(|lifetime| state.elapsed >= lifetime)
+            || self.range.is_some_and(|range| state.traveled >= range)
+            || self.pierce.is_some_and(|pierce| hits > pierce)
+            || (self.despawn_on_hit && hits > 0)
+            || history.bounces > self.bounces
+    }
+}
+
+/// Everything a projectile has run into so far.
+#[derive(Debug, Component, Reflect, Default)]
+pub struct ProjectileHistory {
+    /// Every entity the projectile has hit, which it will never hit again.
+    pub hits: HashSet<Entity>,
+    /// How many times the projectile has bounced off a wall.
+    pub bounces: u32,
+}
+
+impl ProjectileHistory {
+    /// Remembers `target` as hit, returning `false` if it already was.
+    pub fn hit(&mut self, target: Entity) -> bool {
+        self.hits.insert(target)
+    }
+}
+
+pub(crate) fn enforce_projectile_rules(
+    mut commands: Commands,
+    projectiles: Query<(
+        Entity,
+        &ProjectileRules,
+        &ProjectileMotionState,
+        &ProjectileHistory,
+    )>,
+) {
+    for (entity, rules, state, history) in &projectiles {
+        if rules.expired(state, history) {
+            commands.queue(ExpireProjectile(entity));
+        }
+    }
+}
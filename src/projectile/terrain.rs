@@ -0,0 +1,121 @@
+use super::chain::ExpireProjectile;
+use super::motion::{ProjectileMotion, ProjectileMotionState};
+use super::rules::{ProjectileHistory, ProjectileRules};
+use crate::collision::physics::Rigidbody;
+use crate::collision::{
+    BasicCollider, ColliderShape, CollisionEnterEvent, CollisionExitEvent, TilemapCollision,
+};
+use crate::npc::stats::FacingDirection;
+use crate::tile::{TileDepth, TileFlags, TileSlope};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// What a projectile does when it flies into terrain that rises above it,
+/// as set in [`ProjectileData::impact`].
+///
+/// [`ProjectileData::impact`]: super::interfaces::ProjectileData::impact
+#[derive(Debug, Component, Reflect, Serialize, Deserialize, Clone, Default)]
+pub enum TerrainImpact {
+    /// Expires the projectile.
+    #[default]
+    Stop,
+    /// Stops the projectile, and grows its collider to `radius` for a moment
+    /// so it hits everything around it before despawning.
+    Explode { radius: f32 },
+    /// Reflects the projectile off the wall, counting towards [`ProjectileRules::bounces`].
+    Bounce,
+}
+
+/// How far terrain has to rise above a projectile before it counts as a wall instead of the ground.
+const STEP_HEIGHT: f32 = 0.2;
+
+/// Seconds an exploding projectile keeps its grown collider for.
+const EXPLOSION_TIME: f32 = 0.1;
+
+/// Observed by every projectile, running its [`TerrainImpact`] when it hits a wall.
+///
+/// Projectiles moved by a [`Rigidbody`] land on any ground they fall onto instead,
+/// following the height of its slope.
+pub(crate) fn hit_terrain(
+    trigger: Trigger<CollisionEnterEvent>,
+    mut projectiles: Query<(
+        &TerrainImpact,
+        &GlobalTransform,
+        &mut FacingDirection,
+        &mut BasicCollider,
+        &mut ProjectileRules,
+        &mut ProjectileHistory,
+        &ProjectileMotionState,
+        Option<&mut Rigidbody>,
+    )>,
+    tiles: Query<(&GlobalTransform, &TileSlope, &TileFlags), With<TileDepth>>,
+    mut commands: Commands,
+) {
+    let Ok((impact, transform, mut facing, mut collider, mut rules, mut history, state, rigidbody)) =
+        projectiles.get_mut(trigger.this)
+    else {
+        return;
+    };
+    let Ok((tile_transform, slope, flags)) = tiles.get(trigger.other) else {
+        return;
+    };
+
+    let position = transform.translation();
+    let tile_position = tile_transform.translation();
+    let height =
+        tile_position.y + slope.get_height_at_point(flags, position.xz() - tile_position.xz());
+
+    if height <= position.y + STEP_HEIGHT {
+        if let Some(mut rigidbody) = rigidbody {
+            // come to rest once landed, and let the rigidbody follow the slope
+            rigidbody.ground.insert(trigger.other);
+            rigidbody.velocity = Vec3::ZERO;
+        }
+        return;
+    }
+
+    match *impact {
+        TerrainImpact::Stop => commands.queue(ExpireProjectile(trigger.this)),
+        TerrainImpact::Explode { radius } => {
+            collider.shape = ColliderShape::Circle(radius);
+            rules.lifetime = Some(state.elapsed + EXPLOSION_TIME);
+            rules.pierce = None;
+            rules.despawn_on_hit = false;
+            commands
+                .entity(trigger.this)
+                .insert(ProjectileMotion::Linear {
+                    speed: 0.0,
+                    acceleration: 0.0,
+                })
+                .remove::<(Rigidbody, TilemapCollision)>();
+        }
+        TerrainImpact::Bounce => {
+            let wall_center = tile_position.xz() + Vec2::splat(0.5);
+            let relative = position.xz() - wall_center;
+            let normal = if relative.x.abs() >= relative.y.abs() {
+                Vec2::X * relative.x.signum()
+            } else {
+                Vec2::Y * relative.y.signum()
+            };
+
+            history.bounces += 1;
+            if let Ok(direction) = Dir2::new(facing.as_vec2().reflect(normal)) {
+                facing.set(direction);
+            }
+            if let Some(mut rigidbody) = rigidbody {
+                let velocity = rigidbody.velocity.xz().reflect(normal);
+                rigidbody.velocity = velocity.extend(rigidbody.velocity.y).xzy();
+            }
+        }
+    }
+}
+
+/// Observed by every projectile, so projectiles moved by a [`Rigidbody`] fall again once they leave the ground.
+pub(crate) fn leave_terrain(
+    trigger: Trigger<CollisionExitEvent>,
+    mut rigidbodies: Query<&mut Rigidbody>,
+) {
+    if let Ok(mut rigidbody) = rigidbodies.get_mut(trigger.this) {
+        rigidbody.ground.remove(&trigger.other);
+    }
+}
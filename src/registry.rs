@@ -0,0 +1,74 @@
+/// Declares an interned ID type, for things like moves and projectiles
+/// that other plugins can add new kinds of without touching an enum.
+///
+/// IDs are created from their name with `new`, and two IDs are equal if their names are.
+/// They are cheap to copy and compare, and (de)serialize as their name.
+/// As a [`MapKey`](bevy_asset_loader::mapped::MapKey),
+/// the name is taken from the file stem of the asset, e.g. `Tackle` for `Tackle.move.ron`.
+/// Files without a usable name are logged and keyed by their full path instead, so they can't be looked up.
+///
+/// `Default` gives the `Void` ID.
+macro_rules! registry_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Component, Reflect)]
+        #[reflect(opaque)]
+        #[reflect(Debug, PartialEq, Hash, Default)]
+        pub struct $name(bevy::ecs::intern::Interned<str>);
+
+        impl $name {
+            pub fn new(name: &str) -> Self {
+                static INTERNER: bevy::ecs::intern::Interner<str> =
+                    bevy::ecs::intern::Interner::new();
+                Self(INTERNER.intern(name))
+            }
+
+            pub fn name(&self) -> &'static str {
+                self.0.0
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new("Void")
+            }
+        }
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.name())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let name = <String as serde::Deserialize>::deserialize(deserializer)?;
+                Ok(Self::new(&name))
+            }
+        }
+
+        impl bevy_asset_loader::mapped::MapKey for $name {
+            fn from_asset_path(path: &bevy::asset::AssetPath) -> Self {
+                match short_flight::name_from_asset_path(path) {
+                    Some(name) => Self::new(&name),
+                    // the file still needs a key, so give it one nothing will ask for
+                    None => Self::new(&path.to_string()),
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use registry_id;
@@ -1,7 +1,7 @@
+use crate::animation::{AnimType, AnimationData};
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 use serde::{Deserialize, Serialize};
-use crate::animation::{AnimType, AnimationData};
 use std::collections::HashMap;
 
 use crate::assets::AnimationAssets;
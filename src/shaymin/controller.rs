@@ -1,3 +1,4 @@
+use super::loadout::{MoveBinds, Rebinding};
 use super::{Client, ClientQuery};
 use crate::animation::{self, AnimType, cardinal};
 use crate::collision::physics::Rigidbody;
@@ -7,9 +8,13 @@ use crate::collision::{
 };
 use crate::ldtk::TileQuery;
 use crate::moves::Move;
-use crate::moves::interfaces::{MoveList, SpawnMove};
+use crate::moves::charge::{CancelCharge, ChargingMove, ReleaseCharge, StartCharge};
+use crate::moves::interfaces::{
+    MoveCooldowns, MoveData, MoveList, Moves, PowerPoints, SpawnMove, move_ready,
+};
 use crate::npc::animation::AnimationHandler;
 use crate::npc::stats::FacingDirection;
+use crate::npc::status::StatusCondition;
 use crate::tile::{TileDepth, TileFlags, TileSlope};
 use bevy::color::palettes;
 use bevy::platform::collections::HashMap;
@@ -22,7 +27,10 @@ pub fn setup(shaymin: Client, mut commands: Commands) {
             true,
             ColliderShape::Circle(20. / 32. / 2.),
             CollisionLayers::NPC,
-            CollisionLayers::NPC | CollisionLayers::Projectile | CollisionLayers::Wall,
+            CollisionLayers::NPC
+                | CollisionLayers::Projectile
+                | CollisionLayers::Attack
+                | CollisionLayers::Wall,
         ),
         ZHitbox {
             y_tolerance: 0.5,
@@ -61,6 +69,39 @@ pub fn setup(shaymin: Client, mut commands: Commands) {
         ;
 }
 
+/// How long a move press is remembered for while the player can't act on it yet.
+const BUFFER_TIME: f32 = 0.3;
+
+/// Remembers the last move the player pressed,
+/// so it comes out as soon as the current animation can be cancelled or ends.
+#[derive(Debug, Component, Reflect, Default)]
+pub struct MoveBuffer {
+    move_id: Option<Move>,
+    remaining: f32,
+}
+
+impl MoveBuffer {
+    pub fn press(&mut self, move_id: Move) {
+        self.move_id = Some(move_id);
+        self.remaining = BUFFER_TIME;
+    }
+
+    pub fn tick(&mut self, delta: f32) {
+        self.remaining -= delta;
+        if self.remaining <= 0.0 {
+            self.move_id = None;
+        }
+    }
+
+    pub fn buffered(&self) -> Option<Move> {
+        self.move_id
+    }
+
+    pub fn clear(&mut self) {
+        self.move_id = None;
+    }
+}
+
 pub fn control_shaymin(
     shaymin_entity: Client,
     shaymin: ClientQuery<
@@ -69,17 +110,37 @@ pub fn control_shaymin(
             &mut Rigidbody,
             Option<&mut AnimationHandler>,
             &mut FacingDirection,
+            Option<&MoveCooldowns>,
+            Option<&PowerPoints>,
+            Option<&ChargingMove>,
+            &mut MoveBuffer,
+            &Moves,
+            Option<&StatusCondition>,
         ),
         Without<Camera3d>,
     >,
     kb: Res<ButtonInput<KeyCode>>,
+    binds: Res<MoveBinds>,
+    rebinding: Option<Res<Rebinding>>,
     time: Res<Time>,
     move_list: Option<Res<MoveList>>,
+    move_data: Res<Assets<MoveData>>,
     mut commands: Commands,
     mut gizmos: Gizmos,
 ) {
     let enable_gizmos: bool = kb.pressed(KeyCode::KeyX);
-    let (transform, mut rigidbody, anim, mut facing) = shaymin.into_inner();
+    let (
+        transform,
+        mut rigidbody,
+        anim,
+        mut facing,
+        cooldowns,
+        pp,
+        charging,
+        mut move_buffer,
+        moves,
+        status,
+    ) = shaymin.into_inner();
 
     if enable_gizmos {
         gizmos.arrow(
@@ -93,9 +154,7 @@ pub fn control_shaymin(
         return;
     };
 
-    if let Some(data) = anim.animation_data()
-        && let Some(move_list) = move_list
-    {
+    if let Some(move_list) = move_list {
         let input = get_input(&kb).normalize_or_zero();
         let input_dir = Dir2::new(input.xz().normalize_or(Vec2::NEG_Y)).unwrap();
 
@@ -128,33 +187,72 @@ pub fn control_shaymin(
             // );
         }
 
-        if data.is_blocking() {
+        if let Some(charging) = charging {
+            let held = binds
+                .slots(moves)
+                .any(|(key, move_id)| move_id == charging.move_id && kb.pressed(key));
+            if anim.is_locked() {
+                commands.queue(CancelCharge {
+                    parent: *shaymin_entity,
+                });
+            } else if !held {
+                commands.queue(ReleaseCharge {
+                    parent: *shaymin_entity,
+                });
+            }
+            // stand still while charging
+            return;
+        }
+
+        for (key, move_id) in binds.slots(moves) {
+            if kb.just_pressed(key) && rebinding.is_none() {
+                move_buffer.press(move_id);
+            }
+        }
+        move_buffer.tick(time.delta_secs());
+
+        if anim.is_blocking() && !anim.can_cancel() {
             return;
         }
 
         let delta_movement = time.delta_secs() * 30.;
-        const MOVEBINDS: [(KeyCode, Move); 2] = [
-            (KeyCode::KeyK, Move::MagicalLeaf),
-            (KeyCode::KeyO, Move::Tackle),
-        ];
-        for (key, move_id) in MOVEBINDS {
-            if kb.pressed(key) {
-                use_move(
-                    commands,
-                    &mut rigidbody,
-                    &mut anim,
-                    delta_movement,
+        if let Some(move_id) = move_buffer.buffered()
+            && let Some(move_asset) = move_list
+                .data
+                .get(&move_id)
+                .and_then(|handle| move_data.get(handle))
+            && move_ready(move_id, move_asset, cooldowns, pp)
+            && status.is_none_or(|status| !status.kind.prevents_moves())
+        {
+            move_buffer.clear();
+            if move_asset.charge.is_some() {
+                commands.queue(StartCharge {
                     move_id,
-                    &move_list,
-                    *shaymin_entity,
-                );
+                    parent: *shaymin_entity,
+                });
                 return;
-            };
+            }
+            use_move(
+                commands,
+                &mut rigidbody,
+                &mut anim,
+                delta_movement,
+                move_id,
+                &move_list,
+                *shaymin_entity,
+            );
+            return;
+        }
+
+        // inside a cancel window, but with nothing to chain into
+        if anim.is_blocking() {
+            return;
         }
+        let speed = status.map_or(1.0, |status| status.kind.speed_multiplier());
         rigidbody.velocity = rigidbody
             .velocity
             .xz()
-            .move_towards(input.xz() * 1.5, delta_movement)
+            .move_towards(input.xz() * 1.5 * speed, delta_movement)
             .xxy()
             .with_y(rigidbody.velocity.y);
         if input.length_squared() <= 0.0 {
@@ -188,7 +286,11 @@ fn use_move(
 ) {
     let data = move_list.data.get(&move_id).unwrap();
     anim.start_animation(animation::AnimType::AttackShoot);
-    commands.queue(SpawnMove { move_id, parent });
+    commands.queue(SpawnMove {
+        move_id,
+        parent,
+        charge: 0,
+    });
     // rigidbody.velocity = rigidbody
     //     .velocity
     //     .xz()
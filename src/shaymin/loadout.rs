@@ -0,0 +1,185 @@
+use super::{Client, ClientQuery};
+use crate::moves::Move;
+use crate::moves::interfaces::Moves;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How many moves the player can bring at once.
+pub const MOVE_SLOTS: usize = 4;
+
+/// Where the player's loadout is saved, relative to the working directory.
+const LOADOUT_PATH: &str = "loadout.ron";
+
+/// The key that uses the move in each slot of the player's [`Moves`].
+#[derive(Debug, Resource, Reflect, Clone, Serialize, Deserialize)]
+pub struct MoveBinds(pub [KeyCode; MOVE_SLOTS]);
+
+impl Default for MoveBinds {
+    fn default() -> Self {
+        Self([KeyCode::KeyK, KeyCode::KeyO, KeyCode::KeyL, KeyCode::KeyP])
+    }
+}
+
+impl MoveBinds {
+    /// Pairs every move in the loadout with the key bound to its slot.
+    pub fn slots<'a>(&'a self, moves: &'a Moves) -> impl Iterator<Item = (KeyCode, Move)> + 'a {
+        self.0.iter().copied().zip(moves.iter().copied())
+    }
+}
+
+/// The key that starts rebinding a move.
+const REBIND: KeyCode = KeyCode::KeyR;
+
+/// Exists while the player is rebinding one of their [`MoveBinds`].
+///
+/// Moves can't be used until it's done, so pressing their keys doesn't fire them.
+#[derive(Debug, Resource, Default)]
+pub struct Rebinding {
+    /// The slot being rebound, once the player has pressed its current key.
+    slot: Option<usize>,
+}
+
+/// Rebinds a move when the player presses [`REBIND`], then the key of the move, then its new key.
+///
+/// Binding a key another slot already uses swaps the two, and pressing [`REBIND`] again cancels.
+pub(super) fn rebind_moves(
+    kb: Res<ButtonInput<KeyCode>>,
+    rebinding: Option<ResMut<Rebinding>>,
+    mut binds: ResMut<MoveBinds>,
+    mut commands: Commands,
+) {
+    let Some(mut rebinding) = rebinding else {
+        if kb.just_pressed(REBIND) {
+            log::info!("Press the key of the move to rebind");
+            commands.init_resource::<Rebinding>();
+        }
+        return;
+    };
+    let Some(key) = kb.get_just_pressed().next().copied() else {
+        return;
+    };
+    if key == REBIND {
+        log::info!("Stopped rebinding");
+        commands.remove_resource::<Rebinding>();
+        return;
+    }
+
+    match rebinding.slot {
+        None => match binds.0.iter().position(|bind| *bind == key) {
+            Some(slot) => {
+                log::info!("Press the new key for slot {}", slot + 1);
+                rebinding.slot = Some(slot);
+            }
+            None => {
+                log::info!("{:?} is not bound to a move", key);
+                commands.remove_resource::<Rebinding>();
+            }
+        },
+        Some(slot) => {
+            if let Some(other) = binds.0.iter().position(|bind| *bind == key) {
+                binds.0[other] = binds.0[slot];
+            }
+            binds.0[slot] = key;
+            log::info!("Bound slot {} to {:?}", slot + 1, key);
+            commands.remove_resource::<Rebinding>();
+        }
+    }
+}
+
+/// The contents of the loadout save file.
+#[derive(Debug, Serialize, Deserialize)]
+struct LoadoutSave {
+    moves: Moves,
+    binds: MoveBinds,
+}
+
+impl Default for LoadoutSave {
+    fn default() -> Self {
+        Self {
+            moves: Moves(vec![Move::new("MagicalLeaf"), Move::new("Tackle")]),
+            binds: MoveBinds::default(),
+        }
+    }
+}
+
+/// Gives the player the loadout from the save file, or the default one if there is none.
+pub(super) fn load_loadout(shaymin: Client, mut commands: Commands) {
+    let save = short_flight::deserialize_file::<LoadoutSave>(LOADOUT_PATH).unwrap_or_else(|_| {
+        log::info!("No loadout found at {}, using the default", LOADOUT_PATH);
+        LoadoutSave::default()
+    });
+    commands.entity(*shaymin).insert(save.moves);
+    commands.insert_resource(save.binds);
+}
+
+/// Writes the loadout to the save file whenever it changes.
+pub(super) fn save_loadout(moves: ClientQuery<Ref<Moves>>, binds: Res<MoveBinds>) {
+    let moves = moves.into_inner();
+    let moves_changed = moves.is_changed() && !moves.is_added();
+    let binds_changed = binds.is_changed() && !binds.is_added();
+    if !moves_changed && !binds_changed {
+        return;
+    }
+
+    let save = LoadoutSave {
+        moves: moves.clone(),
+        binds: binds.clone(),
+    };
+    if short_flight::serialize_to_file(save, LOADOUT_PATH) {
+        log::info!("Saved loadout to {}", LOADOUT_PATH);
+    }
+}
+
+/// Teaches `target` a move, such as when a [`DialogueAction::LearnMove`] choice is picked.
+///
+/// Goes into `slot` if given, replacing whatever was there, otherwise into the first free slot.
+///
+/// [`DialogueAction::LearnMove`]: crate::dialogue::DialogueAction::LearnMove
+pub struct LearnMove {
+    pub target: Entity,
+    pub move_id: Move,
+    pub slot: Option<usize>,
+}
+
+impl Command for LearnMove {
+    fn apply(self, world: &mut World) {
+        let Some(mut moves) = world.get_mut::<Moves>(self.target) else {
+            log::error!("{} cannot learn moves", self.target);
+            return;
+        };
+        if moves.contains(&self.move_id) {
+            log::info!("{} already knows {:?}", self.target, self.move_id);
+            return;
+        }
+
+        match self.slot {
+            Some(slot) if slot < moves.len() => moves[slot] = self.move_id,
+            _ if moves.len() < MOVE_SLOTS => moves.push(self.move_id),
+            _ => log::info!(
+                "{} has no free slot for {:?}, forget a move first",
+                self.target,
+                self.move_id
+            ),
+        }
+    }
+}
+
+/// Makes `target` forget a move, moving the following moves up a slot.
+///
+/// Queued by [`DialogueAction::ForgetMove`] choices.
+///
+/// [`DialogueAction::ForgetMove`]: crate::dialogue::DialogueAction::ForgetMove
+pub struct ForgetMove {
+    pub target: Entity,
+    pub move_id: Move,
+}
+
+impl Command for ForgetMove {
+    fn apply(self, world: &mut World) {
+        let Some(mut moves) = world.get_mut::<Moves>(self.target) else {
+            log::error!("{} cannot forget moves", self.target);
+            return;
+        };
+        moves.retain(|move_id| *move_id != self.move_id);
+    }
+}
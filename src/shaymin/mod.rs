@@ -4,10 +4,12 @@ use crate::assets::ShortFlightLoadingState;
 use crate::billboard::Billboard;
 use crate::camera::{Mode3D, switch_projection};
 use crate::ldtk::TileQuery;
+use crate::moves::interfaces::{MoveCooldowns, PowerPoints};
 use crate::npc::animation::AnimationHandler;
 use crate::npc::stats::{Damage, FacingDirection, Health};
 use crate::sprite3d::Sprite3dParams;
 use crate::tile::{TileDepth, TileFlags, TileSlope};
+use crate::typing::{ElementType, Typing};
 use assets::ShayminAssets;
 use bevy::prelude::*;
 use bevy_ecs_tilemap::tiles::TileStorage;
@@ -15,6 +17,7 @@ use bevy_ecs_tilemap::tiles::TileStorage;
 mod anim_state;
 pub mod assets;
 mod controller;
+pub mod loadout;
 
 /// Marker component for the client state parent entity.
 /// Used primarily for player logic.
@@ -40,19 +43,32 @@ pub struct ShayminPlugin;
 impl Plugin for ShayminPlugin {
     fn build(&self, app: &mut App) {
         app.world_mut().spawn(Shaymin);
-        app.add_systems(Startup, (setup, controller::setup))
+        app.init_resource::<loadout::MoveBinds>()
+            .register_type::<loadout::MoveBinds>()
+            .add_systems(Startup, (setup, controller::setup, loadout::load_loadout))
             .add_systems(
                 OnEnter(ShortFlightLoadingState::PlayerLoading),
                 insert_animation,
             )
             .add_systems(OnEnter(ShortFlightLoadingState::Done), insert_sprite)
-            .add_systems(FixedUpdate, controller::control_shaymin)
+            .add_systems(
+                FixedUpdate,
+                controller::control_shaymin.run_if(crate::dialogue::not_in_dialogue),
+            )
             .add_systems(
                 PostUpdate,
                 controller::draw_colliders
                     .run_if(|kb: Res<ButtonInput<KeyCode>>| kb.pressed(KeyCode::KeyV)),
             )
             .add_systems(Update, update_mode_3d.before(switch_projection))
+            .add_systems(
+                Update,
+                (
+                    loadout::rebind_moves.run_if(crate::dialogue::not_in_dialogue),
+                    loadout::save_loadout,
+                )
+                    .chain(),
+            )
             .add_systems(OnEnter(ShortFlightLoadingState::FailState), retry);
     }
 }
@@ -63,6 +79,10 @@ fn setup(shaymin: Client, mut commands: Commands) {
         FacingDirection(Dir2::EAST),
         Damage(20),
         Health::new(50),
+        MoveCooldowns::default(),
+        controller::MoveBuffer::default(),
+        PowerPoints::new(20.0, 1.0),
+        Typing(vec![ElementType::Grass]),
     ));
 }
 
@@ -0,0 +1,140 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy_asset_loader::asset_collection::AssetCollection;
+use serde::{Deserialize, Serialize};
+
+/// The elemental type of a creature or an attack.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum ElementType {
+    #[default]
+    Normal,
+    Fire,
+    Water,
+    Electric,
+    Grass,
+    Ice,
+    Fighting,
+    Poison,
+    Ground,
+    Flying,
+    Psychic,
+    Bug,
+    Rock,
+    Ghost,
+    Dragon,
+    Dark,
+    Steel,
+    Fairy,
+}
+
+/// The types of a creature, which decide how much damage it takes from typed attacks.
+#[derive(Debug, Default, Component, Reflect, Clone, Deref, DerefMut, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Typing(pub Vec<ElementType>);
+
+/// The type of an attack, inserted onto move and projectile entities.
+#[derive(Debug, Component, Reflect, Clone)]
+pub struct AttackElement {
+    pub element: ElementType,
+    /// Set if the user of the attack shares its type.
+    pub same_type: bool,
+}
+
+impl AttackElement {
+    /// Creates the element for an attack used by `user`, checking the user's [`Typing`] for a same-type bonus.
+    pub fn for_user(world: &World, element: ElementType, user: Entity) -> Self {
+        Self {
+            element,
+            same_type: world
+                .get::<Typing>(user)
+                .is_some_and(|typing| typing.contains(&element)),
+        }
+    }
+}
+
+#[derive(Resource, AssetCollection)]
+pub(crate) struct TypeChartAssets {
+    #[asset(path = "type_chart.chart.ron")]
+    pub chart: Handle<TypeChart>,
+}
+
+/// How effective each type is against every other type.
+#[derive(Debug, Asset, Reflect, Serialize, Deserialize, Clone)]
+pub(crate) struct TypeChart {
+    /// Multiplier for attacks that share a type with their user.
+    pub same_type_bonus: f32,
+    /// Multiplier of each attacking type against each defending type.
+    ///
+    /// Missing entries are neutral, and a multiplier of `0.0` is an immunity.
+    pub matchups: HashMap<ElementType, HashMap<ElementType, f32>>,
+}
+
+impl TypeChart {
+    pub fn matchup(&self, attack: ElementType, defender: ElementType) -> f32 {
+        self.matchups
+            .get(&attack)
+            .and_then(|matchups| matchups.get(&defender))
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// Total damage multiplier for an attack against a defender with the given types.
+    pub fn damage_multiplier(&self, attack: &AttackElement, defender: Option<&Typing>) -> f32 {
+        let effectiveness: f32 = defender
+            .map(|typing| {
+                typing
+                    .iter()
+                    .map(|defender| self.matchup(attack.element, *defender))
+                    .product()
+            })
+            .unwrap_or(1.0);
+        let same_type_bonus = if attack.same_type {
+            self.same_type_bonus
+        } else {
+            1.0
+        };
+        effectiveness * same_type_bonus
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chart() -> TypeChart {
+        TypeChart {
+            same_type_bonus: 1.5,
+            matchups: HashMap::from_iter([
+                (
+                    ElementType::Grass,
+                    HashMap::from_iter([(ElementType::Rock, 2.0), (ElementType::Ground, 2.0)]),
+                ),
+                (
+                    ElementType::Normal,
+                    HashMap::from_iter([(ElementType::Ghost, 0.0)]),
+                ),
+            ]),
+        }
+    }
+
+    #[test]
+    fn dual_types_stack() {
+        let attack = AttackElement {
+            element: ElementType::Grass,
+            same_type: true,
+        };
+        let defender = Typing(vec![ElementType::Rock, ElementType::Ground]);
+        assert_eq!(chart().damage_multiplier(&attack, Some(&defender)), 6.0);
+    }
+
+    #[test]
+    fn immunity_ignores_bonus() {
+        let attack = AttackElement {
+            element: ElementType::Normal,
+            same_type: true,
+        };
+        let defender = Typing(vec![ElementType::Ghost]);
+        assert_eq!(chart().damage_multiplier(&attack, Some(&defender)), 0.0);
+        assert_eq!(chart().damage_multiplier(&attack, None), 1.5);
+    }
+}